@@ -0,0 +1,145 @@
+use common::{ParseError, Result};
+
+use crate::days::Part;
+
+// Recorded answers of one day, read from `day<N>/answers.toml`. Every table
+// names an input file of that day directory:
+//
+//     ["input.txt"]
+//     part1 = 1778099
+//     part2 = "1623571"
+//
+// A part without a key has no known answer for that input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Expected {
+    pub fn part(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+}
+
+pub fn parse(content: &str) -> Result<Vec<Expected>> {
+    let mut expected: Vec<Expected> = vec![];
+
+    for (i, raw_line) in content.lines().enumerate() {
+        let line_no = i + 1;
+        let line = strip_comment(raw_line).trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let input = header
+                .strip_suffix(']')
+                .map(|input| unquote(input.trim()))
+                .filter(|input| !input.is_empty())
+                .ok_or_else(|| ParseError::line(line_no, raw_line, "expected `[\"input file\"]`"))?;
+
+            expected.push(Expected {
+                input: input.to_string(),
+                part1: None,
+                part2: None,
+            });
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| ParseError::line(line_no, raw_line, "expected `key = value`"))?;
+        let (key, value) = (key.trim(), value.trim());
+
+        let current = expected
+            .last_mut()
+            .ok_or_else(|| ParseError::line(line_no, raw_line, "answer outside of an input table"))?;
+
+        let slot = match key {
+            "part1" => &mut current.part1,
+            "part2" => &mut current.part2,
+            _ => return Err(ParseError::at(line_no, raw_line, key, "expected `part1` or `part2`")),
+        };
+
+        if value.is_empty() {
+            return Err(ParseError::line(line_no, raw_line, "missing answer"));
+        }
+
+        *slot = Some(unquote(value).to_string());
+    }
+
+    Ok(expected)
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+// `#` starts a comment unless it is inside a quoted string.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => (),
+        }
+    }
+
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tables() {
+        let expected = parse(
+            r#"
+# real puzzle input
+["input.txt"]
+part1 = 1778099
+part2 = "1623571"
+
+[example.txt]
+part1 = "CMZ" # crate tops
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                Expected {
+                    input: "input.txt".to_string(),
+                    part1: Some("1778099".to_string()),
+                    part2: Some("1623571".to_string()),
+                },
+                Expected {
+                    input: "example.txt".to_string(),
+                    part1: Some("CMZ".to_string()),
+                    part2: None,
+                },
+            ],
+            expected
+        );
+    }
+
+    #[test]
+    fn reject_unknown_keys() {
+        let error = parse("[\"input.txt\"]\npart3 = 1").unwrap_err();
+        assert_eq!((2, 1, "part3"), (error.line, error.column, error.text.as_str()));
+
+        assert!(parse("part1 = 1").is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+// Command line arguments of a subcommand: positionals in order, plus
+// `--name value` (or `--name=value`) options.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    pub fn parse(raw: &[String]) -> Result<Args, String> {
+        let mut args = Args::default();
+        let mut raw = raw.iter();
+
+        while let Some(arg) = raw.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                args.positional.push(arg.clone());
+                continue;
+            };
+
+            if let Some((name, value)) = name.split_once('=') {
+                args.options.insert(name.to_string(), value.to_string());
+            } else {
+                let value = raw
+                    .next()
+                    .ok_or_else(|| format!("missing value for --{name}"))?;
+                args.options.insert(name.to_string(), value.clone());
+            }
+        }
+
+        Ok(args)
+    }
+
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_positionals_and_options() {
+        let args = Args::parse(&strings(&["4", "--part", "2", "--input=-"])).unwrap();
+
+        assert_eq!(vec!["4"], args.positional);
+        assert_eq!(Some("2"), args.option("part"));
+        assert_eq!(Some("-"), args.option("input"));
+        assert_eq!(None, args.option("verbose"));
+    }
+
+    #[test]
+    fn missing_option_value() {
+        assert!(Args::parse(&strings(&["--part"])).is_err());
+    }
+}
@@ -0,0 +1,277 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use common::{json, Answer, Result, Solution};
+
+use crate::args::Args;
+use crate::days::{self, Day, Part, DAYS};
+use crate::table;
+
+// Wraps the system allocator to count the allocations made by each phase.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// Timings of one phase (`parse`, `part1` or `part2`) over every iteration.
+// Allocations are the median count and size per iteration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phase {
+    pub name: &'static str,
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub allocations: usize,
+    pub allocated_bytes: usize,
+}
+
+// Runs the parse step and each solved part `iterations` times. Parts that
+// have no solver yet are left out.
+pub fn measure<S: Solution>(input: &str, iterations: usize) -> Result<Vec<Phase>> {
+    let parsed = S::parse(input)?;
+
+    let mut phases = vec![phase("parse", iterations, || S::parse(input))];
+
+    for (name, part) in [("part1", Part::One), ("part2", Part::Two)] {
+        if days::run_part::<S>(part, &parsed) == Answer::Unsolved {
+            continue;
+        }
+
+        phases.push(phase(name, iterations, || days::run_part::<S>(part, &parsed)));
+    }
+
+    Ok(phases)
+}
+
+fn phase<T>(name: &'static str, iterations: usize, mut run: impl FnMut() -> T) -> Phase {
+    let mut timings = Vec::with_capacity(iterations);
+    let mut allocations = Vec::with_capacity(iterations);
+    let mut allocated_bytes = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+        let bytes_before = ALLOCATED_BYTES.load(Ordering::Relaxed);
+        let start = Instant::now();
+
+        let output = black_box(run());
+
+        timings.push(start.elapsed());
+        allocations.push(ALLOCATIONS.load(Ordering::Relaxed) - allocations_before);
+        allocated_bytes.push(ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes_before);
+
+        drop(output);
+    }
+
+    timings.sort();
+    allocations.sort();
+    allocated_bytes.sort();
+
+    Phase {
+        name,
+        min: timings[0],
+        median: percentile(&timings, 50),
+        p95: percentile(&timings, 95),
+        allocations: percentile(&allocations, 50),
+        allocated_bytes: percentile(&allocated_bytes, 50),
+    }
+}
+
+// Nearest-rank percentile of sorted, non-empty samples.
+fn percentile<T: Copy>(sorted: &[T], p: usize) -> T {
+    let rank = (sorted.len() * p).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Table,
+    Json,
+}
+
+struct Report {
+    day: u8,
+    input: String,
+    phases: Vec<Phase>,
+}
+
+// `aoc bench [<day>] [--iterations N] [--input PATH] [--format table|json]`:
+// without `--input` a day is measured on its `input.txt`, falling back to
+// `example.txt`.
+pub fn bench(raw: &[String]) -> std::result::Result<(), String> {
+    let args = Args::parse(raw)?;
+
+    let iterations = match args.option("iterations") {
+        None => 100,
+        Some(n) => n
+            .parse::<usize>()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| format!("invalid iteration count `{n}`"))?,
+    };
+
+    let format = match args.option("format") {
+        None | Some("table") => Format::Table,
+        Some("json") => Format::Json,
+        Some(format) => return Err(format!("unknown format `{format}`, expected table or json")),
+    };
+
+    let days: Vec<&Day> = match args.positional.first() {
+        Some(day) => vec![crate::parse_day(Some(day))?],
+        None if args.option("input").is_some() => {
+            return Err("--input needs a day to run it against".to_string())
+        }
+        None => DAYS.iter().collect(),
+    };
+
+    let mut reports = vec![];
+    for day in days {
+        let Some(path) = args.option("input").map(str::to_string).or_else(|| default_input(day)) else {
+            eprintln!("day {}: no input.txt or example.txt, skipped", day.number);
+            continue;
+        };
+
+        let (source, input) = crate::read_input(day.number, Some(&path))?;
+        let phases = (day.bench)(&input, iterations).map_err(|e| crate::diagnostic(&source, &e))?;
+
+        reports.push(Report {
+            day: day.number,
+            input: source,
+            phases,
+        });
+    }
+
+    match format {
+        Format::Table => print_table(&reports, iterations),
+        Format::Json => println!("{}", to_json(&reports, iterations)),
+    }
+
+    Ok(())
+}
+
+fn default_input(day: &Day) -> Option<String> {
+    ["input.txt", "example.txt"]
+        .iter()
+        .map(|name| format!("day{}/{name}", day.number))
+        .find(|path| Path::new(path).exists())
+}
+
+fn print_table(reports: &[Report], iterations: usize) {
+    let rows: Vec<Vec<String>> = reports
+        .iter()
+        .flat_map(|report| {
+            report.phases.iter().map(|phase| {
+                vec![
+                    report.day.to_string(),
+                    report.input.clone(),
+                    phase.name.to_string(),
+                    human(phase.min),
+                    human(phase.median),
+                    human(phase.p95),
+                    phase.allocations.to_string(),
+                    phase.allocated_bytes.to_string(),
+                ]
+            })
+        })
+        .collect();
+
+    println!("{iterations} iterations per phase\n");
+    table::print(
+        &["day", "input", "phase", "min", "median", "p95", "allocs", "bytes"],
+        &rows,
+    );
+}
+
+fn human(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+
+    if nanos < 1_000 {
+        format!("{nanos}ns")
+    } else if nanos < 1_000_000 {
+        format!("{:.2}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+fn to_json(reports: &[Report], iterations: usize) -> String {
+    let results: Vec<String> = reports
+        .iter()
+        .flat_map(|report| {
+            report.phases.iter().map(|phase| {
+                format!(
+                    "{{\"day\":{},\"input\":{},\"phase\":{},\"min_ns\":{},\"median_ns\":{},\"p95_ns\":{},\"allocations\":{},\"allocated_bytes\":{}}}",
+                    report.day,
+                    json::string(&report.input),
+                    json::string(phase.name),
+                    phase.min.as_nanos(),
+                    phase.median.as_nanos(),
+                    phase.p95.as_nanos(),
+                    phase.allocations,
+                    phase.allocated_bytes,
+                )
+            })
+        })
+        .collect();
+
+    format!(
+        "{{\"iterations\":{iterations},\"results\":[{}]}}",
+        results.join(",")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let samples: Vec<u32> = (1..=20).collect();
+
+        assert_eq!(10, percentile(&samples, 50));
+        assert_eq!(19, percentile(&samples, 95));
+        assert_eq!(7, percentile(&[7], 95));
+    }
+
+    #[test]
+    fn measure_every_solved_phase() {
+        let phases = measure::<day11::Day11>(include_str!("../../day11/example.txt"), 3).unwrap();
+        let names: Vec<&str> = phases.iter().map(|phase| phase.name).collect();
+
+        assert_eq!(vec!["parse", "part1"], names);
+        assert!(phases.iter().all(|phase| phase.min <= phase.median && phase.median <= phase.p95));
+        assert!(phases[0].allocations > 0);
+    }
+
+    #[test]
+    fn human_durations() {
+        assert_eq!("999ns", human(Duration::from_nanos(999)));
+        assert_eq!("1.50µs", human(Duration::from_nanos(1_500)));
+        assert_eq!("2.00ms", human(Duration::from_millis(2)));
+    }
+}
@@ -0,0 +1,74 @@
+use day4::IntervalSet;
+
+use crate::args::Args;
+
+const USAGE: &str = "usage: aoc day4 <groups|merge|gaps|depth|at SECTION> [--input PATH|-]";
+
+// `aoc day4 <report>`: per group reports, or coverage reports over every
+// assignment of the input.
+pub fn report(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw)?;
+
+    let (source, input) = crate::read_input(4, args.option("input"))?;
+    let assignments = day4::parse(&input).map_err(|e| crate::diagnostic(&source, &e))?;
+    let set = IntervalSet::from_assignments(&assignments);
+
+    let positional: Vec<&str> = args.positional.iter().map(String::as_str).collect();
+    match positional.as_slice() {
+        ["groups"] => {
+            for (i, assignment) in assignments.iter().enumerate() {
+                let ranges: Vec<String> = assignment.ranges.iter().map(ToString::to_string).collect();
+                let contained: Vec<String> = assignment
+                    .contained_members()
+                    .iter()
+                    .map(|&member| assignment.ranges[member].to_string())
+                    .collect();
+                let intersection = assignment
+                    .intersection()
+                    .map_or("none".to_string(), |range| range.to_string());
+
+                println!(
+                    "line {}: {}  contained: [{}]  overlapping pairs: {}  intersection: {intersection}",
+                    i + 1,
+                    ranges.join(","),
+                    contained.join(", "),
+                    assignment.overlapping_pairs(),
+                );
+            }
+        }
+        ["merge"] => {
+            for span in set.merged() {
+                println!("{span}");
+            }
+        }
+        ["gaps"] => {
+            let gaps = set.gaps();
+            if gaps.is_empty() {
+                println!("every section between the first and the last one is covered");
+            }
+
+            for gap in gaps {
+                println!("{gap} ({} sections)", gap.sections());
+            }
+        }
+        ["depth"] => {
+            for (span, depth) in set.coverage() {
+                println!("{span}: {depth}");
+            }
+
+            if let Some((max, spans)) = set.max_depth() {
+                let spans: Vec<String> = spans.iter().map(ToString::to_string).collect();
+                println!("max depth {max} at {}", spans.join(", "));
+            }
+        }
+        ["at", section] => {
+            let section = section
+                .parse::<isize>()
+                .map_err(|_| format!("invalid section `{section}`"))?;
+            println!("section {section}: covered by {} ranges", set.depth_at(section));
+        }
+        _ => return Err(USAGE.to_string()),
+    }
+
+    Ok(())
+}
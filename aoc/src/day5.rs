@@ -0,0 +1,234 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::thread;
+use std::time::Duration;
+
+use day5::{Crane, Target};
+
+use crate::args::Args;
+
+const USAGE: &str = "usage:
+    aoc day5 tops [--crane 9000,9001,N] [--input PATH|-]
+    aoc day5 stacks [--crane 9000|9001|N] [--output FILE] [--input PATH|-]
+    aoc day5 trace [--crane 9000|9001|N] [--delay MS|enter] [--output FILE] [--input PATH|-]
+    aoc day5 solve (--tops LABELS | --layout FILE) [--crane 9000|9001|N] [--max-states N] [--input PATH|-]
+    aoc day5 script [--crane 9000|9001|N] [--input PATH|-]
+    aoc day5 stream [--crane 9000|9001|N] [--progress N] [--input PATH|-]";
+
+// `aoc day5 <command>`: runs the rearrangement with the chosen cranes.
+pub fn report(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw)?;
+
+    let positional: Vec<&str> = args.positional.iter().map(String::as_str).collect();
+    if positional == ["stream"] {
+        return stream(&args);
+    }
+
+    let (source, input) = crate::read_input(5, args.option("input"))?;
+
+    if positional == ["script"] {
+        return script(&source, &input, args.option("crane"));
+    }
+
+    let procedure = day5::parse(&input).map_err(|e| crate::diagnostic(&source, &e))?;
+
+    match positional.as_slice() {
+        ["tops"] => {
+            for crane in cranes(args.option("crane"))? {
+                println!("{}: {}", crane.name(), procedure.run(crane.as_ref()));
+            }
+        }
+        ["stacks"] => {
+            let stacks = procedure.finish(single_crane(args.option("crane"))?.as_ref());
+
+            match args.option("output") {
+                Some(path) => save(path, &format!("{stacks}\n"))?,
+                None => println!("{stacks}"),
+            }
+        }
+        ["trace"] => {
+            let crane = single_crane(args.option("crane"))?;
+            let steps = procedure.trace(crane.as_ref());
+            let trace = day5::render_trace(procedure.stacks(), &steps);
+
+            match args.option("delay") {
+                None => write_trace(&trace, args.option("output"), &mut io::stdout().lock())?,
+                Some("enter") if source == "<stdin>" => {
+                    return Err("--delay enter needs --input PATH, stdin holds the transcript".to_string())
+                }
+                Some(delay) => {
+                    if let Some(path) = args.option("output") {
+                        save(path, &trace)?;
+                    }
+                    animate(procedure.stacks(), &steps, delay)?
+                }
+            }
+        }
+        ["solve"] => {
+            let target = target(args.option("tops"), args.option("layout"))?;
+            let max_states = match args.option("max-states") {
+                Some(n) => n.parse().map_err(|_| format!("invalid --max-states `{n}`"))?,
+                None => 1_000_000,
+            };
+
+            let crane = single_crane(args.option("crane"))?;
+            let moves = day5::solve(procedure.stacks(), &target, crane.as_ref(), max_states)
+                .map_err(|e| e.to_string())?;
+
+            // a procedure that `aoc day5` reads back
+            println!("{}\n", procedure.stacks());
+            for mv in moves {
+                println!("{mv}");
+            }
+        }
+        _ => return Err(USAGE.to_string()),
+    }
+
+    Ok(())
+}
+
+// Prints the trace to `out`, saving it to `output` as well when given.
+fn write_trace(trace: &str, output: Option<&str>, out: &mut dyn Write) -> Result<(), String> {
+    if let Some(path) = output {
+        save(path, trace)?;
+    }
+
+    out.write_all(trace.as_bytes()).map_err(|e| e.to_string())
+}
+
+fn save(path: &str, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|e| format!("cannot write {path}: {e}"))
+}
+
+// A comma separated list of crane models, both CrateMovers by default.
+fn cranes(models: Option<&str>) -> Result<Vec<Box<dyn Crane>>, String> {
+    models
+        .unwrap_or("9000,9001")
+        .split(',')
+        .map(|model| {
+            day5::crane_from_str(model.trim())
+                .ok_or_else(|| format!("unknown crane `{model}`, expected 9000, 9001 or a lift capacity"))
+        })
+        .collect()
+}
+
+// `aoc day5 script`: the input's commands may also undo, swap, reverse,
+// test the top crates and call macros.
+fn script(source: &str, input: &str, crane: Option<&str>) -> Result<(), String> {
+    let script = day5::parse_script(input).map_err(|e| crate::diagnostic(source, &e))?;
+    let stacks = script
+        .run(single_crane(crane)?.as_ref())
+        .map_err(|e| format!("{source}:{}: error: {}", e.line, e.error))?;
+
+    println!("{stacks}");
+    println!("tops: {}", stacks.tops());
+    Ok(())
+}
+
+// `aoc day5 stream`: applies the moves while reading them, for move lists
+// too big to load, with a progress line on stderr every `--progress` moves.
+fn stream(args: &Args) -> Result<(), String> {
+    let every = match args.option("progress") {
+        Some(n) => n.parse().map_err(|_| format!("invalid --progress `{n}`"))?,
+        None => 0,
+    };
+    let crane = single_crane(args.option("crane"))?;
+
+    let (source, reader): (String, Box<dyn BufRead>) = match args.option("input") {
+        Some("-") => ("<stdin>".to_string(), Box::new(io::stdin().lock())),
+        path => {
+            let path = path.unwrap_or("day5/input.txt");
+            let file = File::open(path).map_err(|e| format!("can't read {path}: {e}"))?;
+            (path.to_string(), Box::new(BufReader::new(file)))
+        }
+    };
+
+    let mut report = |progress: day5::Progress| {
+        eprintln!("{source}: {} moves done, line {}", progress.moves, progress.line);
+    };
+    let stacks = day5::run_stream(reader, crane.as_ref(), every, &mut report).map_err(|e| match e {
+        day5::StreamError::Parse(e) => crate::diagnostic(&source, &e),
+        day5::StreamError::Io(e) => format!("can't read {source}: {e}"),
+    })?;
+
+    println!("{}: {}", crane.name(), stacks.tops());
+    Ok(())
+}
+
+// Either the wanted top labels or a file holding the wanted drawing.
+fn target(tops: Option<&str>, layout: Option<&str>) -> Result<Target, String> {
+    match (tops, layout) {
+        (Some(tops), None) => Ok(Target::Tops(tops.to_string())),
+        (None, Some(path)) => {
+            let drawing = fs::read_to_string(path).map_err(|e| format!("can't read {path}: {e}"))?;
+            let layout = drawing.parse().map_err(|e| crate::diagnostic(path, &e))?;
+            Ok(Target::Layout(layout))
+        }
+        _ => Err("expected either --tops or --layout".to_string()),
+    }
+}
+
+// One crane model, the CrateMover 9000 by default.
+fn single_crane(model: Option<&str>) -> Result<Box<dyn Crane>, String> {
+    let mut models = cranes(Some(model.unwrap_or("9000")))?;
+
+    match (models.pop(), models.is_empty()) {
+        (Some(crane), true) => Ok(crane),
+        _ => Err("expected a single crane".to_string()),
+    }
+}
+
+// Redraws the stacks in place after each move, waiting `delay` milliseconds
+// or for Enter between frames.
+fn animate(initial: &day5::Stacks, steps: &[day5::Step], delay: &str) -> Result<(), String> {
+    let pause = match delay {
+        "enter" => None,
+        ms => Some(Duration::from_millis(
+            ms.parse().map_err(|_| format!("invalid --delay `{ms}`, expected milliseconds or enter"))?,
+        )),
+    };
+
+    let frames = std::iter::once(format!("initial\n{initial}"))
+        .chain(steps.iter().map(|step| step.to_string()));
+
+    for frame in frames {
+        println!("\x1b[2J\x1b[H{frame}");
+        io::stdout().flush().map_err(|e| e.to_string())?;
+
+        match pause {
+            Some(duration) => thread::sleep(duration),
+            None => {
+                io::stdin().read_line(&mut String::new()).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::process;
+
+    use super::*;
+
+    #[test]
+    fn trace_to_a_file_and_stdout() {
+        let procedure = day5::parse(include_str!("../../day5/example.txt")).unwrap();
+        let crane = single_crane(None).unwrap();
+        let trace = day5::render_trace(procedure.stacks(), &procedure.trace(crane.as_ref()));
+
+        let path = env::temp_dir().join(format!("day5-trace-{}.txt", process::id()));
+        let path = path.to_str().unwrap();
+        let mut out = vec![];
+        let result = write_trace(&trace, Some(path), &mut out);
+        let saved = fs::read_to_string(path);
+        let _ = fs::remove_file(path);
+        result.unwrap();
+
+        assert_eq!(trace, saved.unwrap());
+        assert_eq!(trace, String::from_utf8(out).unwrap());
+        assert!(trace.contains("move 1 from 2 to 1"));
+    }
+}
@@ -0,0 +1,162 @@
+use std::env;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use day7::{Aggregate, Aggregated, FileSystem, Format, Query, Shell, View};
+
+use crate::args::Args;
+
+const USAGE: &str = "usage:
+    aoc day7 <tree|list|du|top N> [--depth N] [--format text|json|csv] [--input PATH|-]
+    aoc day7 plan [--capacity N] [--required N] [--input PATH|-]
+    aoc day7 query EXPR [--aggregate count|sum(F)|min_by(F)|max_by(F)] [--format text|json|csv] [--input PATH|-]
+    aoc day7 materialize [--dir DIR] [--input PATH|-]
+    aoc day7 transcript DIR
+    aoc day7 shell [--input PATH]
+    aoc day7 diff BEFORE AFTER [--format text|json|csv]";
+
+// `aoc day7 <report>`: views of the filesystem rebuilt from the transcript.
+pub fn report(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw)?;
+
+    match args.positional.as_slice() {
+        [command, dir] if command == "transcript" => return transcript(Path::new(dir)),
+        [command, before, after] if command == "diff" => {
+            let format: Format = args.option("format").unwrap_or("text").parse()?;
+            return diff(before, after, format);
+        }
+        _ => {}
+    }
+
+    let (source, input) = crate::read_input(7, args.option("input"))?;
+    let fs = parse(&source, &input)?;
+
+    let format: Format = args.option("format").unwrap_or("text").parse()?;
+
+    let positional: Vec<&str> = args.positional.iter().map(String::as_str).collect();
+    let view = match positional.as_slice() {
+        ["query", query] => return self::query(&fs, query, args.option("aggregate"), format),
+        ["plan"] => {
+            let number = |name: &str, default: u64| match args.option(name) {
+                Some(n) => n.parse().map_err(|_| format!("invalid --{name} `{n}`")),
+                None => Ok(default),
+            };
+
+            let plan = fs
+                .plan(number("capacity", 70000000)?, number("required", 30000000)?)
+                .map_err(|e| e.to_string())?;
+            print!("{plan}");
+            return Ok(());
+        }
+        ["materialize"] => {
+            let dir = match args.option("dir") {
+                Some(dir) => PathBuf::from(dir),
+                None => {
+                    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.subsec_nanos());
+                    env::temp_dir().join(format!("day7-{}-{nanos}", process::id()))
+                }
+            };
+
+            fs.materialize(&dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+            println!("{}", dir.display());
+            return Ok(());
+        }
+        ["shell"] if source == "<stdin>" => {
+            return Err("shell needs --input PATH, stdin holds the commands".to_string())
+        }
+        ["shell"] => return shell(&fs),
+        ["tree"] => View::Tree,
+        ["list"] => View::List,
+        ["du"] => View::Du,
+        ["top", n] => View::Top(n.parse().map_err(|_| format!("invalid count `{n}`"))?),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let max_depth = match args.option("depth") {
+        Some(depth) => Some(depth.parse().map_err(|_| format!("invalid --depth `{depth}`"))?),
+        None => None,
+    };
+
+    print!("{}", day7::render(view, &fs.report(view, max_depth), format));
+    Ok(())
+}
+
+// The tree of a transcript, with what was skipped on stderr.
+fn parse(source: &str, input: &str) -> Result<FileSystem, String> {
+    let mut fs = FileSystem::new();
+    fs.parse_cmds(input).map_err(|e| crate::diagnostic(source, &e))?;
+
+    for warning in fs.warnings() {
+        eprintln!("{}", crate::warning(source, warning));
+    }
+
+    Ok(fs)
+}
+
+// `aoc day7 query EXPR`: the matching entries, or their aggregate.
+fn query(fs: &FileSystem, query: &str, aggregate: Option<&str>, format: Format) -> Result<(), String> {
+    let query: Query = query.parse().map_err(|e| crate::diagnostic("query", &e))?;
+    let entries = fs.query(&query);
+
+    let Some(aggregate) = aggregate else {
+        print!("{}", day7::render(View::List, &entries, format));
+        return Ok(());
+    };
+
+    match aggregate.parse::<Aggregate>()?.apply(&entries) {
+        Aggregated::Number(n) => println!("{n}"),
+        Aggregated::Entry(Some(entry)) => print!("{}", day7::render(View::List, &[entry], format)),
+        Aggregated::Entry(None) => return Err("no entry matches the query".to_string()),
+    }
+
+    Ok(())
+}
+
+// `aoc day7 transcript DIR`: the terminal output of exploring `DIR`.
+fn transcript(dir: &Path) -> Result<(), String> {
+    let mut out = BufWriter::new(io::stdout().lock());
+
+    day7::write_transcript(dir, &mut out)
+        .and_then(|()| out.flush())
+        .map_err(|e| format!("{}: {e}", dir.display()))
+}
+
+// `aoc day7 shell`: commands from stdin run against the filesystem until
+// `exit` or the end of input.
+fn shell(fs: &FileSystem) -> Result<(), String> {
+    let Some(mut shell) = Shell::new(fs) else {
+        return Err("the transcript never enters a directory".to_string());
+    };
+    let mut lines = io::stdin().lock().lines();
+
+    loop {
+        print!("{}$ ", shell.pwd());
+        io::stdout().flush().map_err(|e| e.to_string())?;
+
+        let Some(line) = lines.next() else {
+            println!();
+            return Ok(());
+        };
+        let line = line.map_err(|e| e.to_string())?;
+
+        match shell.run(&line) {
+            Ok(Some(output)) => print!("{output}"),
+            Ok(None) => return Ok(()),
+            Err(e) => eprintln!("error: {e}"),
+        }
+    }
+}
+
+// `aoc day7 diff BEFORE AFTER`: how the tree changed between two sessions.
+fn diff(before: &str, after: &str, format: Format) -> Result<(), String> {
+    let read = |path: &str| -> Result<FileSystem, String> {
+        let (source, input) = crate::read_input(7, Some(path))?;
+        parse(&source, &input)
+    };
+
+    let changes = day7::diff(&read(before)?, &read(after)?);
+    print!("{}", day7::render_diff(&changes, format));
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use common::{Answer, ParseError, Result, Solution};
+
+use crate::bench::{self, Phase};
+
+pub type Answers = Vec<(Part, Answer)>;
+
+// The answers to the requested parts, with what parsing skipped.
+pub struct Solved {
+    pub answers: Answers,
+    pub warnings: Vec<ParseError>,
+}
+
+// A day with a Rust solver, erased to plain functions so that the runner,
+// `verify` and `bench` can loop over every day the same way.
+pub struct Day {
+    pub number: u8,
+    pub solve: fn(&[Part], &str) -> Result<Solved>,
+    pub bench: fn(&str, usize) -> Result<Vec<Phase>>,
+}
+
+const fn day<S: Solution>(number: u8) -> Day {
+    Day {
+        number,
+        solve: answer::<S>,
+        bench: bench::measure::<S>,
+    }
+}
+
+// In the order the runner lists them.
+pub const DAYS: &[Day] = &[
+    day::<day1::Day1>(1),
+    day::<day2::Day2>(2),
+    day::<day3::Day3>(3),
+    day::<day4::Day4>(4),
+    day::<day5::Day5>(5),
+    day::<day7::Day7>(7),
+    day::<day11::Day11>(11),
+];
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+pub fn numbers() -> Vec<u8> {
+    DAYS.iter().map(|day| day.number).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+// Parses the raw puzzle input once and answers the requested parts.
+fn answer<S: Solution>(parts: &[Part], input: &str) -> Result<Solved> {
+    let input = S::parse(input)?;
+
+    let answers = parts
+        .iter()
+        .map(|&part| (part, run_part::<S>(part, &input)))
+        .collect();
+
+    Ok(Solved {
+        answers,
+        warnings: S::warnings(&input).to_vec(),
+    })
+}
+
+pub fn run_part<S: Solution>(part: Part, input: &S::Input<'_>) -> Answer {
+    match part {
+        Part::One => S::part1(input),
+        Part::Two => S::part2(input),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pass_on_parse_warnings() {
+        let solved = (find(7).unwrap().solve)(&[Part::One], "$ cd /\n$ pwd\n/\n$ ls\n5 a\n").unwrap();
+
+        assert_eq!(vec![(Part::One, Answer::Number(5))], solved.answers);
+        assert_eq!(vec!["`pwd` skipped"], solved.warnings.iter().map(|e| e.message.as_str()).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,160 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+use args::Args;
+use common::{Answer, ParseError};
+use days::{Answers, Day, Part};
+
+mod answers;
+mod args;
+mod bench;
+mod day4;
+mod day5;
+mod day7;
+mod days;
+mod table;
+mod verify;
+
+const USAGE: &str = "usage:
+    aoc run <day> [--part 1|2] [--input PATH|-]
+    aoc verify [<day>]
+    aoc bench [<day>] [--iterations N] [--input PATH] [--format table|json]
+    aoc day4 <groups|merge|gaps|depth|at SECTION> [--input PATH|-]
+    aoc day5 tops [--crane 9000,9001,N] [--input PATH|-]
+    aoc day5 stacks [--crane 9000|9001|N] [--output FILE] [--input PATH|-]
+    aoc day5 trace [--crane 9000|9001|N] [--delay MS|enter] [--output FILE] [--input PATH|-]
+    aoc day5 solve (--tops LABELS | --layout FILE) [--crane 9000|9001|N] [--max-states N] [--input PATH|-]
+    aoc day5 script [--crane 9000|9001|N] [--input PATH|-]
+    aoc day5 stream [--crane 9000|9001|N] [--progress N] [--input PATH|-]
+    aoc day7 <tree|list|du|top N> [--depth N] [--format text|json|csv] [--input PATH|-]
+    aoc day7 plan [--capacity N] [--required N] [--input PATH|-]
+    aoc day7 query EXPR [--aggregate count|sum(F)|min_by(F)|max_by(F)] [--format text|json|csv] [--input PATH|-]
+    aoc day7 materialize [--dir DIR] [--input PATH|-]
+    aoc day7 transcript DIR
+    aoc day7 shell [--input PATH]
+    aoc day7 diff BEFORE AFTER [--format text|json|csv]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("run") => run(&args[1..]),
+        Some("verify") => verify::verify(&args[1..]),
+        Some("bench") => bench::bench(&args[1..]),
+        Some("day4") => day4::report(&args[1..]),
+        Some("day5") => day5::report(&args[1..]),
+        Some("day7") => day7::report(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw)?;
+
+    let day = parse_day(args.positional.first())?;
+    let parts = parse_parts(args.option("part"))?;
+    let (source, input) = read_input(day.number, args.option("input"))?;
+
+    let solved = (day.solve)(&parts, &input).map_err(|e| diagnostic(&source, &e))?;
+
+    for e in &solved.warnings {
+        eprintln!("{}", warning(&source, e));
+    }
+    print_answers(day.number, &solved.answers, &mut io::stdout().lock())
+}
+
+// Prints the answers, failing with the parts the input has no answer for.
+fn print_answers(day: u8, answers: &Answers, out: &mut dyn Write) -> Result<(), String> {
+    let mut failures = vec![];
+
+    for (part, answer) in answers {
+        match answer {
+            Answer::Failed(message) => failures.push(format!("day {day} part {}: error: {message}", part.number())),
+            answer => writeln!(out, "day {day} part {}: {answer}", part.number()).map_err(|e| e.to_string())?,
+        }
+    }
+
+    match failures.is_empty() {
+        true => Ok(()),
+        false => Err(failures.join("\n")),
+    }
+}
+
+pub(crate) fn parse_day(day: Option<&String>) -> Result<&'static Day, String> {
+    let day = day.ok_or_else(|| USAGE.to_string())?;
+
+    day.parse::<u8>()
+        .ok()
+        .and_then(days::find)
+        .ok_or_else(|| format!("no solver for day `{day}`, available days: {:?}", days::numbers()))
+}
+
+fn parse_parts(part: Option<&str>) -> Result<Vec<Part>, String> {
+    match part {
+        None => Ok(vec![Part::One, Part::Two]),
+        Some("1") => Ok(vec![Part::One]),
+        Some("2") => Ok(vec![Part::Two]),
+        Some(part) => Err(format!("invalid part `{part}`, expected 1 or 2")),
+    }
+}
+
+// `-` reads stdin, no path reads `day<N>/input.txt` from the current directory.
+// Returns the name to report errors against along with the content.
+pub(crate) fn read_input(day: u8, path: Option<&str>) -> Result<(String, String), String> {
+    let path = match path {
+        Some("-") => {
+            let mut buffer = String::new();
+            io::stdin()
+                .read_to_string(&mut buffer)
+                .map_err(|e| format!("can't read stdin: {e}"))?;
+            return Ok(("<stdin>".to_string(), buffer));
+        }
+        Some(path) => path.to_string(),
+        None => format!("day{day}/input.txt"),
+    };
+
+    let input = fs::read_to_string(&path).map_err(|e| format!("can't read {path}: {e}"))?;
+    Ok((path, input))
+}
+
+// Formats a parse error the way compilers do: `file:line:column: message`.
+pub(crate) fn diagnostic(source: &str, e: &ParseError) -> String {
+    format!(
+        "{source}:{}:{}: error: {}\n    {}",
+        e.line, e.column, e.message, e.text
+    )
+}
+
+// The same for what was skipped rather than rejected.
+pub(crate) fn warning(source: &str, e: &ParseError) -> String {
+    format!(
+        "{source}:{}:{}: warning: {}\n    {}",
+        e.line, e.column, e.message, e.text
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fail_on_unanswerable_parts() {
+        let over = "$ cd /\n$ ls\n5000000000 big\n4000000000 bigger";
+        let solved = (days::find(7).unwrap().solve)(&[Part::One, Part::Two], over).unwrap();
+
+        let mut out = vec![];
+        let error = print_answers(7, &solved.answers, &mut out).unwrap_err();
+        assert_eq!("day 7 part 1: 0\n", String::from_utf8(out).unwrap());
+        assert_eq!("day 7 part 2: error: disk already over capacity: 9000000000 used out of 70000000", error);
+    }
+}
@@ -0,0 +1,154 @@
+use std::fs;
+use std::path::Path;
+
+use common::Answer;
+
+use crate::answers::{self, Expected};
+use crate::args::Args;
+use crate::days::{Day, Part, DAYS};
+use crate::table;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Fail,
+    Missing,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Fail => "FAIL",
+            Status::Missing => "missing",
+        }
+    }
+}
+
+struct Check {
+    day: u8,
+    part: Part,
+    input: String,
+    expected: String,
+    actual: String,
+    status: Status,
+}
+
+// `aoc verify [<day>]`: runs every day against the inputs recorded in its
+// `answers.toml` and fails when any answer changed.
+pub fn verify(raw: &[String]) -> Result<(), String> {
+    let args = Args::parse(raw)?;
+
+    let days: Vec<&Day> = match args.positional.first() {
+        Some(day) => vec![crate::parse_day(Some(day))?],
+        None => DAYS.iter().collect(),
+    };
+
+    let mut checks: Vec<Check> = vec![];
+    for day in days {
+        checks.extend(check_day(day)?);
+    }
+
+    print_table(&checks);
+
+    let count = |status: Status| checks.iter().filter(|c| c.status == status).count();
+    let failed = count(Status::Fail);
+    println!(
+        "\n{} passed, {failed} failed, {} missing",
+        count(Status::Pass),
+        count(Status::Missing)
+    );
+
+    if failed > 0 {
+        return Err(format!("{failed} of {} checks failed", checks.len()));
+    }
+
+    Ok(())
+}
+
+fn check_day(day: &Day) -> Result<Vec<Check>, String> {
+    let dir = format!("day{}", day.number);
+    let path = Path::new(&dir).join("answers.toml");
+
+    if !path.exists() {
+        let missing = |part| Check {
+            day: day.number,
+            part,
+            input: "-".to_string(),
+            expected: "-".to_string(),
+            actual: "-".to_string(),
+            status: Status::Missing,
+        };
+        return Ok(vec![missing(Part::One), missing(Part::Two)]);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("can't read {}: {e}", path.display()))?;
+    let recorded = answers::parse(&content)
+        .map_err(|e| crate::diagnostic(&path.display().to_string(), &e))?;
+
+    let mut checks = vec![];
+    for expected in recorded {
+        checks.extend(check_input(day, &dir, &expected));
+    }
+
+    Ok(checks)
+}
+
+fn check_input(day: &Day, dir: &str, expected: &Expected) -> Vec<Check> {
+    let parts = [Part::One, Part::Two];
+    let path = Path::new(dir).join(&expected.input);
+
+    // with whether it is an error, which fails even without an expected answer
+    let actual: Vec<(String, bool)> = match fs::read_to_string(&path) {
+        Err(e) => vec![(format!("can't read input: {e}"), true); parts.len()],
+        Ok(input) => match (day.solve)(&parts, &input) {
+            Ok(solved) => solved
+                .answers
+                .iter()
+                .map(|(_, answer)| (answer.to_string(), matches!(answer, Answer::Failed(_))))
+                .collect(),
+            Err(e) => vec![(format!("error: {e}"), true); parts.len()],
+        },
+    };
+
+    parts
+        .iter()
+        .zip(actual)
+        .map(|(&part, (actual, failed))| {
+            let (expected, status) = match expected.part(part) {
+                _ if failed => (expected.part(part).unwrap_or("-").to_string(), Status::Fail),
+                None => ("-".to_string(), Status::Missing),
+                Some(answer) if answer == actual => (answer.to_string(), Status::Pass),
+                Some(answer) => (answer.to_string(), Status::Fail),
+            };
+
+            Check {
+                day: day.number,
+                part,
+                input: path.display().to_string(),
+                expected,
+                actual,
+                status,
+            }
+        })
+        .collect()
+}
+
+fn print_table(checks: &[Check]) {
+    let rows: Vec<Vec<String>> = checks
+        .iter()
+        .map(|c| {
+            vec![
+                c.day.to_string(),
+                c.part.number().to_string(),
+                c.input.clone(),
+                c.expected.clone(),
+                c.actual.clone(),
+                c.status.label().to_string(),
+            ]
+        })
+        .collect();
+
+    table::print(&["day", "part", "input", "expected", "actual", "status"], &rows);
+}
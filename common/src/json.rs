@@ -0,0 +1,34 @@
+// Just enough JSON writing for the reports the crates print, without pulling
+// in a serializer.
+
+// `value` as a quoted and escaped JSON string.
+pub fn string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_strings() {
+        assert_eq!(r#""day7/input.txt""#, string("day7/input.txt"));
+        assert_eq!(r#""a \"b\"\\c\n\u0001""#, string("a \"b\"\\c\n\u{1}"));
+    }
+}
@@ -0,0 +1,176 @@
+use std::error::Error;
+use std::fmt;
+
+pub mod json;
+
+pub type Result<T> = std::result::Result<T, ParseError>;
+
+// Every day parses its puzzle input once and answers both parts from it.
+pub trait Solution {
+    type Input<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>>;
+    fn part1(input: &Self::Input<'_>) -> Answer;
+    fn part2(input: &Self::Input<'_>) -> Answer;
+
+    // What parsing skipped rather than rejected, for the runner to report.
+    fn warnings<'i>(_input: &'i Self::Input<'_>) -> &'i [ParseError] {
+        &[]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    // Wide enough for every integer type an answer comes from.
+    Number(i128),
+    Text(String),
+    // The part has no solver yet.
+    Unsolved,
+    // The input has no answer for the part, and why.
+    Failed(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{number}"),
+            Answer::Text(text) => write!(f, "{text}"),
+            Answer::Unsolved => write!(f, "not solved yet"),
+            Answer::Failed(message) => write!(f, "error: {message}"),
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(number: $t) -> Answer {
+                    Answer::Number(i128::try_from(number).expect("answer does not fit in 128 bits"))
+                }
+            }
+        )*
+    };
+}
+
+answer_from_number!(u16, u32, u64, usize, isize, i32, i64);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Answer {
+        Answer::Text(text.to_string())
+    }
+}
+
+// Where and why an input could not be parsed. Lines and columns start at 1,
+// `text` is the offending part of the line. Parsers of a single line or
+// fragment report line 1, and the caller that knows where it sits moves the
+// error there, with `offset` or by setting `line`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, text: &str, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line,
+            column,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    // Points at `fragment`, which has to be a slice of `source` (the whole
+    // line) so that the column can be derived from its offset.
+    pub fn at(line: usize, source: &str, fragment: &str, message: impl Into<String>) -> ParseError {
+        let offset = (fragment.as_ptr() as usize)
+            .checked_sub(source.as_ptr() as usize)
+            .filter(|offset| offset + fragment.len() <= source.len())
+            .unwrap_or(0);
+        let column = source[..offset].chars().count() + 1;
+
+        ParseError::new(line, column, fragment, message)
+    }
+
+    // Moves an error reported against `fragment` alone to where `fragment`
+    // sits in `source`, on the given line.
+    pub fn offset(self, line: usize, source: &str, fragment: &str) -> ParseError {
+        let start = ParseError::at(line, source, fragment, "").column;
+
+        ParseError {
+            line,
+            column: start + self.column - 1,
+            ..self
+        }
+    }
+
+    // Points at the whole line.
+    pub fn line(line: usize, source: &str, message: impl Into<String>) -> ParseError {
+        ParseError::new(line, 1, source, message)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}: `{}`",
+            self.line, self.column, self.message, self.text
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_answers() {
+        assert_eq!("24000", Answer::from(24000usize).to_string());
+        assert_eq!("18446744073709551615", Answer::from(u64::MAX).to_string());
+        assert_eq!("-3", Answer::from(-3i32).to_string());
+        assert_eq!("CMZ", Answer::from("CMZ").to_string());
+        assert_eq!("not solved yet", Answer::Unsolved.to_string());
+        assert_eq!("error: no answer", Answer::Failed("no answer".to_string()).to_string());
+    }
+
+    #[test]
+    fn locate_fragment_in_line() {
+        let line = "move 1 frm 2 to 1";
+        let error = ParseError::at(3, line, &line[7..10], "expected `from`");
+
+        assert_eq!(3, error.line);
+        assert_eq!(8, error.column);
+        assert_eq!("frm", error.text);
+        assert_eq!(
+            "line 3, column 8: expected `from`: `frm`",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn offset_into_line() {
+        let line = "2-4,6-x";
+        let range = &line[4..];
+        let error = ParseError::at(1, range, &range[2..], "invalid section").offset(5, line, range);
+
+        assert_eq!((5, 7, "x"), (error.line, error.column, error.text.as_str()));
+    }
+
+    #[test]
+    fn unrelated_fragment_points_at_line_start() {
+        let error = ParseError::at(1, "A Y", "elsewhere", "unknown shape");
+        assert_eq!(1, error.column);
+    }
+}
@@ -0,0 +1,104 @@
+use common::{Answer, ParseError, Result, Solution};
+
+// Total calories carried by each elf, sorted from the lightest to the heaviest.
+pub fn calories(input: &str) -> Result<Vec<isize>> {
+    let mut calories: Vec<isize> = Vec::new();
+
+    let mut calorie_per_elf: Vec<isize> = Vec::new();
+
+    for (i, str_val) in input.split('\n').enumerate() {
+        if str_val.is_empty() {
+            if !calorie_per_elf.is_empty() {
+                calories.push(calorie_per_elf.iter().sum());
+                calorie_per_elf.clear();
+            }
+            continue;
+        }
+
+        let i_calorie = str_val
+            .parse::<isize>()
+            .map_err(|_| ParseError::line(i + 1, str_val, "cannot convert the calorie to integer"))?;
+        calorie_per_elf.push(i_calorie);
+    }
+
+    if !calorie_per_elf.is_empty() {
+        calories.push(calorie_per_elf.iter().sum());
+    }
+
+    if calories.is_empty() {
+        return Err(ParseError::new(1, 1, "", "no calories in the input"));
+    }
+
+    calories.sort();
+    Ok(calories)
+}
+
+// Part 1
+pub fn max_calories(calories: &[isize]) -> isize {
+    calories
+        .last()
+        .copied()
+        .expect("cannot get the greatest calorie value")
+}
+
+// Part 2
+pub fn top_three(calories: &[isize]) -> isize {
+    calories[calories.len().saturating_sub(3)..].iter().sum()
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input<'a> = Vec<isize>;
+
+    fn parse(input: &str) -> Result<Vec<isize>> {
+        calories(input)
+    }
+
+    fn part1(calories: &Vec<isize>) -> Answer {
+        max_calories(calories).into()
+    }
+
+    fn part2(calories: &Vec<isize>) -> Answer {
+        top_three(calories).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+
+    #[test]
+    fn sum_per_elf() {
+        assert_eq!(vec![4000, 6000, 10000, 11000, 24000], calories(INPUT).unwrap());
+    }
+
+    #[test]
+    fn greatest_and_top_three() {
+        let calories = calories(INPUT).unwrap();
+        assert_eq!(24000, max_calories(&calories));
+        assert_eq!(45000, top_three(&calories));
+    }
+
+    #[test]
+    fn reject_non_numeric_calories() {
+        let error = calories("1000\n\n20x0").unwrap_err();
+        assert_eq!((3, 1, "20x0"), (error.line, error.column, error.text.as_str()));
+        assert!(calories("").is_err());
+    }
+}
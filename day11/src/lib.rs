@@ -0,0 +1,320 @@
+use common::{Answer, ParseError, Result, Solution};
+
+#[derive(Debug, Clone, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Operation {
+    Double,         // old + old
+    Add(u64),       // old + number
+    Square,         // old * old
+    Multiply(u64),  // old * number
+}
+
+#[derive(Debug, Clone, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Monkey {
+    id: usize,
+    items: Vec<u64>,
+    operation: Operation,
+    divisible: u64,
+    if_true: usize,
+    if_false: usize,
+    inspect_count: usize,
+}
+
+impl Monkey {
+    // `None` when the new worry level does not fit in 64 bits.
+    fn calculate(&self, old: u64) -> Option<u64> {
+        match self.operation {
+            Operation::Double => old.checked_mul(2),
+            Operation::Add(number) => old.checked_add(number),
+            Operation::Square => old.checked_mul(old),
+            Operation::Multiply(number) => old.checked_mul(number),
+        }
+    }
+}
+
+fn parse_operation(operation: &str) -> Result<Operation> {
+    let parts: Vec<&str> = operation.split_whitespace().collect();
+
+    if parts.len() != 3 || parts[0] != "old" {
+        return Err(ParseError::line(1, operation, "expected `old <op> <operand>`"));
+    }
+
+    let operation = match (parts[1], parts[2]) {
+        ("*", "old") => Operation::Square,
+        ("+", "old") => Operation::Double,
+        (operator, num_str) => {
+            let number = num_str
+                .parse::<u64>()
+                .map_err(|_| ParseError::at(1, operation, num_str, "invalid operand 2!"))?;
+            match operator {
+                "*" => Operation::Multiply(number),
+                "+" => Operation::Add(number),
+                _ => return Err(ParseError::at(1, operation, operator, "invalid operation syntax!")),
+            }
+        }
+    };
+
+    Ok(operation)
+}
+
+// The lines every monkey's block needs, as they start.
+const REQUIRED: [&str; 4] = ["Operation", "Test", "If true", "If false"];
+
+pub fn parse_monkeys(input: &str) -> Result<Vec<Monkey>> {
+    let mut monkeys: Vec<Monkey> = vec![];
+    // where each `If true`/`If false` target was given, checked once all monkeys are known
+    let mut targets: Vec<(usize, &str, &str)> = vec![];
+    // each monkey's header, and which of `REQUIRED` its block has
+    let mut blocks: Vec<(usize, &str, [bool; 4])> = vec![];
+
+    for (i, raw_line) in input.lines().enumerate() {
+        let line_no = i + 1;
+        let line = raw_line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let number = |text: &str| {
+            text.parse::<usize>()
+                .map_err(|_| ParseError::at(line_no, raw_line, text, "expected a number"))
+        };
+        let last_word = || line.split_whitespace().last().unwrap_or(line);
+
+        if line.starts_with("Monkey") {
+            let monkey_id = number(line.trim_start_matches("Monkey").trim().trim_end_matches(':'))?;
+            monkeys.push(Monkey {
+                id: monkey_id,
+                items: Vec::new(),
+                operation: Operation::Double,  // Default operation
+                divisible: 0,
+                if_true: 0,
+                if_false: 0,
+                inspect_count: 0,
+            });
+            blocks.push((line_no, raw_line, [false; 4]));
+            continue;
+        }
+
+        let monkey = monkeys
+            .last_mut()
+            .ok_or_else(|| ParseError::line(line_no, raw_line, "expected a `Monkey N:` header first"))?;
+        let value = line.split_once(": ").map(|(_, value)| value);
+        if let Some(i) = REQUIRED.iter().position(|name| line.starts_with(name)) {
+            if let Some((_, _, seen)) = blocks.last_mut() {
+                seen[i] = true;
+            }
+        }
+
+        if line.starts_with("Starting items") {
+            let items = value.unwrap_or_default().split(", ")
+                .map(|s| s.parse::<u64>().map_err(|_| ParseError::at(line_no, raw_line, s, "invalid worry level")))
+                .collect::<Result<Vec<u64>>>()?;
+            monkey.items = items;
+
+        } else if line.starts_with("Operation") {
+            let operation_str = value
+                .and_then(|value| value.split_once('='))
+                .map(|(_, operation)| operation.trim())
+                .ok_or_else(|| ParseError::line(line_no, raw_line, "expected `Operation: new = ...`"))?;
+            let operation = parse_operation(operation_str)
+                .map_err(|e| e.offset(line_no, raw_line, operation_str))?;
+            monkey.operation = operation;
+
+        } else if line.starts_with("Test") {
+            let divisible = last_word();
+            monkey.divisible = match divisible.parse::<u64>() {
+                Ok(divisible) if divisible > 0 => divisible,
+                _ => return Err(ParseError::at(line_no, raw_line, divisible, "expected a positive divisor")),
+            };
+
+        } else if line.starts_with("If true") {
+            monkey.if_true = number(last_word())?;
+            targets.push((line_no, raw_line, last_word()));
+            
+        } else if line.starts_with("If false") {
+            monkey.if_false = number(last_word())?;
+            targets.push((line_no, raw_line, last_word()));
+
+        } else {
+            return Err(ParseError::line(line_no, raw_line, "unknown monkey attribute"));
+        }
+    }
+
+    if monkeys.len() < 2 {
+        let last_line = input.lines().count().max(1);
+        let text = input.lines().last().unwrap_or_default();
+        return Err(ParseError::line(last_line, text, "expected at least two monkeys"));
+    }
+
+    for (monkey, (line_no, raw_line, seen)) in monkeys.iter().zip(blocks) {
+        if let Some(i) = seen.iter().position(|seen| !seen) {
+            let message = format!("monkey {} has no `{}` line", monkey.id, REQUIRED[i]);
+            return Err(ParseError::line(line_no, raw_line, message));
+        }
+    }
+
+    for (line_no, raw_line, target) in targets {
+        if !monkeys.iter().any(|monkey| monkey.id.to_string() == target) {
+            return Err(ParseError::at(line_no, raw_line, target, format!("no monkey {target}")));
+        }
+    }
+
+    Ok(monkeys)
+}
+
+// Part 1: level of monkey business after 20 rounds, or the monkey whose
+// worry level overflowed.
+pub fn monkey_business(mut monkeys: Vec<Monkey>) -> std::result::Result<usize, String> {
+    for _ in 0..20 {
+        for i in 0..monkeys.len() {
+            let if_true = monkeys[i].if_true;
+            let if_false = monkeys[i].if_false;
+
+            for _ in 0..monkeys[i].items.len() {
+                if monkeys[i].items.is_empty() {
+                    break;
+                }
+                let current_item = monkeys[i].items.remove(0);
+                let worry_level_result = monkeys[i]
+                    .calculate(current_item)
+                    .ok_or_else(|| format!("worry level of {current_item} overflows at monkey {}", monkeys[i].id))?
+                    / 3;
+                
+                let target = if (worry_level_result % monkeys[i].divisible) == 0 {
+                    if_true
+                } else {
+                    if_false
+                };
+
+                if let Some(monkey) = monkeys.iter_mut().find(|m: &&mut Monkey| m.id == target) {
+                    monkey.items.push(worry_level_result);
+                }
+            
+                monkeys[i].inspect_count += 1;
+            }
+    
+        }
+    }
+
+    monkeys.sort_by_key(|m| std::cmp::Reverse(m.inspect_count));
+    Ok(monkeys[0].inspect_count * monkeys[1].inspect_count)
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input<'a> = Vec<Monkey>;
+
+    fn parse(input: &str) -> Result<Vec<Monkey>> {
+        parse_monkeys(input)
+    }
+
+    fn part1(monkeys: &Vec<Monkey>) -> Answer {
+        match monkey_business(monkeys.clone()) {
+            Ok(business) => business.into(),
+            Err(message) => Answer::Failed(message),
+        }
+    }
+
+    fn part2(_: &Vec<Monkey>) -> Answer {
+        Answer::Unsolved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+
+    #[test]
+    fn parse_operations() {
+        assert_eq!(Operation::Square, parse_operation("old * old").unwrap());
+        assert_eq!(Operation::Double, parse_operation("old + old").unwrap());
+        assert_eq!(Operation::Multiply(19), parse_operation("old * 19").unwrap());
+        assert_eq!(Operation::Add(6), parse_operation("old + 6").unwrap());
+    }
+
+    #[test]
+    fn part1() {
+        let monkeys = parse_monkeys(INPUT).unwrap();
+        assert_eq!(4, monkeys.len());
+        assert_eq!(Ok(10605), monkey_business(monkeys));
+    }
+
+    #[test]
+    fn report_bad_monkeys() {
+        let error = parse_monkeys("Monkey 0:\n  Operation: new = old / 3").unwrap_err();
+        assert_eq!((2, 24, "/"), (error.line, error.column, error.text.as_str()));
+
+        let error = parse_monkeys("  Starting items: 79").unwrap_err();
+        assert_eq!(1, error.line);
+    }
+
+    #[test]
+    fn reject_too_few_monkeys() {
+        assert_eq!("expected at least two monkeys", parse_monkeys("").unwrap_err().message);
+
+        let one = INPUT.split("\n\n").next().unwrap();
+        let error = parse_monkeys(one).unwrap_err();
+        assert_eq!((6, "expected at least two monkeys"), (error.line, error.message.as_str()));
+    }
+
+    #[test]
+    fn reject_unknown_targets() {
+        let input = INPUT.replacen("If false: throw to monkey 3", "If false: throw to monkey 7", 1);
+        let error = parse_monkeys(&input).unwrap_err();
+        assert_eq!((6, 31, "no monkey 7"), (error.line, error.column, error.message.as_str()));
+    }
+
+    #[test]
+    fn require_every_line() {
+        let input = INPUT.replacen("  Test: divisible by 23\n", "", 1);
+        let error = parse_monkeys(&input).unwrap_err();
+        assert_eq!((1, "monkey 0 has no `Test` line"), (error.line, error.message.as_str()));
+
+        let input = INPUT.replacen("    If true: throw to monkey 0\n", "", 1);
+        let error = parse_monkeys(&input).unwrap_err();
+        assert_eq!((22, "monkey 3 has no `If true` line"), (error.line, error.message.as_str()));
+
+        let input = INPUT.replacen("divisible by 23", "divisible by 0", 1);
+        assert_eq!("expected a positive divisor", parse_monkeys(&input).unwrap_err().message);
+    }
+
+    #[test]
+    fn report_overflow() {
+        let input = INPUT.replacen("Starting items: 79, 60, 97", "Starting items: 100000", 1);
+        assert!(monkey_business(parse_monkeys(&input).unwrap()).is_ok());
+
+        let input = INPUT.replacen("Starting items: 79, 60, 97", "Starting items: 5000000000", 1);
+        let monkeys = parse_monkeys(&input).unwrap();
+        assert_eq!(Err("worry level of 5000000000 overflows at monkey 2".to_string()), monkey_business(monkeys.clone()));
+        assert!(matches!(Day11::part1(&monkeys), Answer::Failed(_)));
+    }
+}
@@ -1,7 +1,9 @@
 use std::cmp::Ordering;
 
+use common::{Answer, ParseError, Result, Solution};
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Rochambeau{
+pub enum Rochambeau{
   Rock = 1,
   Paper = 2,
   Scissor = 3,
@@ -45,20 +47,17 @@ pub struct PlayerScore {
   pub player2: u16,
 }
 
-fn parse(input_str: String) -> (Rochambeau, Rochambeau) {
-  let input_str: Vec<&str> = input_str.split(' ').collect();
-  
-  let player1: Option<Rochambeau> = Rochambeau::from_str(input_str[0]);
-  if player1.is_none() {
-    panic!("undefined type")
-  }
+fn parse(input_str: String) -> Result<(Rochambeau, Rochambeau)> {
+  let shapes: Vec<&str> = input_str.split(' ').collect();
 
-  let player2: Option<Rochambeau> = Rochambeau::from_str(input_str[1]);
-  if player2.is_none() {
-    panic!("undefined type")
+  if shapes.len() != 2 {
+    return Err(ParseError::line(1, &input_str, "expected two shapes separated by a space"));
   }
 
-  (player1.unwrap(),player2.unwrap())
+  let shape = |s: &str| Rochambeau::from_str(s)
+    .ok_or_else(|| ParseError::at(1, &input_str, s, "undefined type"));
+
+  Ok((shape(shapes[0])?, shape(shapes[1])?))
 }
 
 fn calculate_player_score(score: &mut PlayerScore, player1: Rochambeau, player2: Rochambeau) {
@@ -102,16 +101,16 @@ fn ultra_top_secret_strategy(player1: Rochambeau, player2: Rochambeau) -> (Rocha
   (player1, player2)
 }
 
-pub fn play(str_shapes: String) -> PlayerScore {
+type Strategy = fn(Rochambeau, Rochambeau) -> (Rochambeau, Rochambeau);
+
+fn score_rounds(rounds: &[(Rochambeau, Rochambeau)], strategy: Strategy) -> PlayerScore {
   let mut score = PlayerScore{
     player1: 0,
     player2: 0
   };
 
-  str_shapes.lines().for_each(|line| {
-    let (pl1,pl2) = parse(line.to_string());
-    
-    let (pl1,pl2) = ultra_top_secret_strategy(pl1,pl2);
+  rounds.iter().for_each(|&(pl1, pl2)| {
+    let (pl1,pl2) = strategy(pl1,pl2);
 
     calculate_player_score(&mut score, pl1, pl2);
   });
@@ -119,6 +118,47 @@ pub fn play(str_shapes: String) -> PlayerScore {
   score
 }
 
+fn parse_rounds(str_shapes: &str) -> Result<Vec<(Rochambeau, Rochambeau)>> {
+  str_shapes
+    .lines()
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty())
+    .map(|(i, line)| parse(line.to_string()).map_err(|e| ParseError { line: i + 1, ..e }))
+    .collect()
+}
+
+fn as_written(player1: Rochambeau, player2: Rochambeau) -> (Rochambeau, Rochambeau) {
+  (player1, player2)
+}
+
+// Part 1: the second column is the shape to play.
+pub fn play_as_written(str_shapes: String) -> Result<PlayerScore> {
+  Ok(score_rounds(&parse_rounds(&str_shapes)?, as_written))
+}
+
+// Part 2: the second column is the outcome to reach.
+pub fn play(str_shapes: String) -> Result<PlayerScore> {
+  Ok(score_rounds(&parse_rounds(&str_shapes)?, ultra_top_secret_strategy))
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+  type Input<'a> = Vec<(Rochambeau, Rochambeau)>;
+
+  fn parse(input: &str) -> Result<Vec<(Rochambeau, Rochambeau)>> {
+    parse_rounds(input)
+  }
+
+  fn part1(rounds: &Vec<(Rochambeau, Rochambeau)>) -> Answer {
+    score_rounds(rounds, as_written).player2.into()
+  }
+
+  fn part2(rounds: &Vec<(Rochambeau, Rochambeau)>) -> Answer {
+    score_rounds(rounds, ultra_top_secret_strategy).player2.into()
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use std::cmp::Ordering;
@@ -129,7 +169,7 @@ mod tests {
   fn parse_and_compare() {
     let content = "A Y";
 
-    let (pl1, pl2) = parse(content.to_string());
+    let (pl1, pl2) = parse(content.to_string()).unwrap();
     assert_eq!(Some(Ordering::Less), pl1.partial_cmp(&pl2))
   }
 
@@ -143,9 +183,9 @@ C Z
 
     let mut perline_iter = contents.lines();
 
-    let round1 = parse(perline_iter.next().unwrap().to_string());
-    let round2 = parse(perline_iter.next().unwrap().to_string());
-    let round3 = parse(perline_iter.next().unwrap().to_string());
+    let round1 = parse(perline_iter.next().unwrap().to_string()).unwrap();
+    let round2 = parse(perline_iter.next().unwrap().to_string()).unwrap();
+    let round3 = parse(perline_iter.next().unwrap().to_string()).unwrap();
 
     assert!(round1.0 < round1.1);
     assert!(round2.0 > round2.1);
@@ -153,7 +193,6 @@ C Z
   }
 
   #[test]
-  #[ignore]
   fn play_with_ultra_top_secret_strategy() {
     let contents = "\
 A Y
@@ -163,13 +202,13 @@ C Z
 
     let mut perline_iter = contents.lines();
 
-    let mut round1 = parse(perline_iter.next().unwrap().to_string());
+    let mut round1 = parse(perline_iter.next().unwrap().to_string()).unwrap();
     round1 = ultra_top_secret_strategy(round1.0, round1.1);
 
-    let mut round2 = parse(perline_iter.next().unwrap().to_string());
+    let mut round2 = parse(perline_iter.next().unwrap().to_string()).unwrap();
     round2 = ultra_top_secret_strategy(round2.0, round2.1);
 
-    let mut round3 = parse(perline_iter.next().unwrap().to_string());
+    let mut round3 = parse(perline_iter.next().unwrap().to_string()).unwrap();
     round3 = ultra_top_secret_strategy(round3.0, round3.1);
 
     assert!(round1.0 == round1.1);
@@ -192,9 +231,9 @@ C Z
     
     let mut perline_iter = contents.lines();
 
-    let round1 = parse(perline_iter.next().unwrap().to_string());
-    let round2 = parse(perline_iter.next().unwrap().to_string());
-    let round3 = parse(perline_iter.next().unwrap().to_string());
+    let round1 = parse(perline_iter.next().unwrap().to_string()).unwrap();
+    let round2 = parse(perline_iter.next().unwrap().to_string()).unwrap();
+    let round3 = parse(perline_iter.next().unwrap().to_string()).unwrap();
 
     calculate_player_score(&mut score, round1.0, round1.1);
     assert_eq!(1, score.player1);
@@ -208,4 +247,13 @@ C Z
     assert_eq!(15, score.player1);
     assert_eq!(15, score.player2);
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn reject_undefined_shapes() {
+    let error = parse_rounds("A Y\nB Q\n").unwrap_err();
+    assert_eq!((2, 3, "Q"), (error.line, error.column, error.text.as_str()));
+
+    let error = parse_rounds("A Y\nC\n").unwrap_err();
+    assert_eq!((2, 1, "C"), (error.line, error.column, error.text.as_str()));
+  }
+}
@@ -1,4 +1,4 @@
-use std::io::{Read, BufReader};
+use common::{Answer, ParseError, Result, Solution};
 
 fn detect_compartment(rucksack: &str) -> (&str, &str) {
   let median = rucksack.len() / 2;
@@ -6,22 +6,22 @@ fn detect_compartment(rucksack: &str) -> (&str, &str) {
   (first, last)
 }
 
-fn common_char(x: &str, y: &str) -> char {
-  let mut result: char = 'a';
+fn common_char(x: &str, y: &str) -> Option<char> {
+  let mut result = None;
 
   for c in x.chars() {
     if y.contains(c) {
-      result = c;
+      result = Some(c);
     }
   }
 
   result
 }
 
-fn find_common_char_multiple(items: &Vec<&str>) -> char {
-  let mut result: char = 'a';
+fn find_common_char_multiple(items: &[&str]) -> Option<char> {
+  let mut result = None;
 
-  let mut base_item: Vec<char> = items[0].chars().collect();
+  let base_item: Vec<char> = items[0].chars().collect();
 
   for bi in &base_item {
     let mut found_in_all_lines = false;
@@ -36,7 +36,7 @@ fn find_common_char_multiple(items: &Vec<&str>) -> char {
     }
 
     if found_in_all_lines {
-      result = *bi;
+      result = Some(*bi);
       break;
     }
     
@@ -78,32 +78,74 @@ fn find_priority(c: char) -> usize {
   }
 }
 
-// Part 1
-pub fn exec(input: String) -> usize {
-  let result:usize = input.lines().map(|c| {
+// Part 1, over rucksacks from `parse`, which have an item in both compartments
+pub fn exec(rucksacks: &[&str]) -> usize {
+  let result:usize = rucksacks.iter().map(|c| {
     let (l,f) = detect_compartment(c);
-    let common_c = common_char(l,f);
-    find_priority(common_c)
+    common_char(l,f).map_or(0, find_priority)
   }).sum();
 
   result
 }
 
-// Part 2
-pub fn exec_multiple(input: String) -> usize {
-  let mut buf: Vec<&str> = Vec::new();
-  let mut priorities: Vec<usize> = Vec::new();
-
-  for line in input.lines() {
-    buf.push(line);
-    if buf.len() == 3 {
-      let common_c = find_common_char_multiple(&buf);
-      priorities.push(find_priority(common_c));
-      buf.clear();
+// Part 2, over rucksacks from `parse`, which come in groups of three sharing an item
+pub fn exec_multiple(rucksacks: &[&str]) -> usize {
+  rucksacks
+    .chunks(3)
+    .map(|group| find_common_char_multiple(group).map_or(0, find_priority))
+    .sum()
+}
+
+// Every item is a letter, both compartments hold the same number of items
+// and share one, and the rucksacks come in groups of three sharing one.
+pub fn parse(input: &str) -> Result<Vec<&str>> {
+  let rucksacks = input.lines().enumerate().map(|(i, rucksack)| {
+    if let Some((at, c)) = rucksack.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+      let item = &rucksack[at..at + c.len_utf8()];
+      return Err(ParseError::at(i + 1, rucksack, item, "items must be letters"));
+    }
+
+    if rucksack.len() % 2 != 0 {
+      return Err(ParseError::line(i + 1, rucksack, "odd number of items"));
+    }
+
+    let (first, last) = detect_compartment(rucksack);
+    if common_char(first, last).is_none() {
+      return Err(ParseError::line(i + 1, rucksack, "compartments share no item"));
+    }
+
+    Ok(rucksack)
+  }).collect::<Result<Vec<&str>>>()?;
+
+  for (g, group) in rucksacks.chunks(3).enumerate() {
+    let line = g * 3 + 1;
+    if group.len() < 3 {
+      return Err(ParseError::line(line, group[0], "expected groups of three rucksacks"));
+    }
+    if find_common_char_multiple(group).is_none() {
+      return Err(ParseError::line(line, group[0], "group of three shares no item"));
     }
   }
 
-  priorities.iter().sum()
+  Ok(rucksacks)
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+  type Input<'a> = Vec<&'a str>;
+
+  fn parse(input: &str) -> Result<Vec<&str>> {
+    parse(input)
+  }
+
+  fn part1(rucksacks: &Vec<&str>) -> Answer {
+    exec(rucksacks).into()
+  }
+
+  fn part2(rucksacks: &Vec<&str>) -> Answer {
+    exec_multiple(rucksacks).into()
+  }
 }
 
 #[cfg(test)]
@@ -129,15 +171,15 @@ mod tests {
   fn find_common_char() {
     let (f, l) = detect_compartment("vJrwpWtwJgWrhcsFMMfFFhFp");
     let c = common_char(f, l);
-    assert_eq!('p', c);
+    assert_eq!(Some('p'), c);
 
     let (f, l) = detect_compartment("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL");
     let c = common_char(f, l);
-    assert_eq!('L', c);
+    assert_eq!(Some('L'), c);
 
     let (f, l) = detect_compartment("PmmdzqPrVvPwwTWBwg");
     let c = common_char(f, l);
-    assert_eq!('P', c);
+    assert_eq!(Some('P'), c);
   }
 
   #[test]
@@ -156,7 +198,7 @@ mod tests {
     ];
 
     let result = find_common_char_multiple(&items);
-    assert_eq!('r', result);
+    assert_eq!(Some('r'), result);
 
     let items = vec![
       "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
@@ -165,7 +207,25 @@ mod tests {
   ];
 
   let result = find_common_char_multiple(&items);
-  assert_eq!('Z', result);
+  assert_eq!(Some('Z'), result);
     
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn reject_invalid_rucksacks() {
+    let error = parse("vJrwpWtwJgWrhcsFMMfFFhFp\nab1d").unwrap_err();
+    assert_eq!((2, 3, "1"), (error.line, error.column, error.text.as_str()));
+
+    let error = parse("abc").unwrap_err();
+    assert_eq!("odd number of items", error.message);
+
+    let error = parse("abcd\nefgh").unwrap_err();
+    assert_eq!((1, "compartments share no item"), (error.line, error.message.as_str()));
+
+    let error = parse("abca\nefge").unwrap_err();
+    assert_eq!((1, "expected groups of three rucksacks"), (error.line, error.message.as_str()));
+
+    let error = parse("abca\nabca\nabca\nabca\ncdcd\nefef").unwrap_err();
+    assert_eq!((4, "group of three shares no item"), (error.line, error.message.as_str()));
+  }
+}
@@ -0,0 +1,71 @@
+use crate::SectionRange;
+
+// One line of the input: the ranges assigned to a group of elves, `2-4,6-8`
+// for a pair or `2-4,6-8,3-5` for a bigger team.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+  pub ranges: Vec<SectionRange>,
+}
+
+impl Assignment {
+  // Every unordered pair of members, by index.
+  pub fn pairs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..self.ranges.len()).flat_map(move |i| (i + 1..self.ranges.len()).map(move |j| (i, j)))
+  }
+
+  // Members whose whole range is also covered by another member.
+  pub fn contained_members(&self) -> Vec<usize> {
+    (0..self.ranges.len())
+      .filter(|&i| {
+        self.ranges.iter().enumerate().any(|(j, other)| i != j && other.contains_range(&self.ranges[i]))
+      })
+      .collect()
+  }
+
+  // Number of member pairs sharing at least one section.
+  pub fn overlapping_pairs(&self) -> usize {
+    self
+      .pairs()
+      .filter(|&(i, j)| self.ranges[i].overlaps(&self.ranges[j]))
+      .count()
+  }
+
+  // Sections assigned to every member of the group.
+  pub fn intersection(&self) -> Option<SectionRange> {
+    let (first, rest) = self.ranges.split_first()?;
+    rest.iter().try_fold(*first, |common, range| common.intersection(range))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assignment(input: &str) -> Assignment {
+    Assignment {
+      ranges: input.split(',').map(|range| range.parse().unwrap()).collect(),
+    }
+  }
+
+  #[test]
+  fn it_reports_groups() {
+    let group = assignment("2-4,6-8,3-5");
+
+    assert_eq!(vec![(0, 1), (0, 2), (1, 2)], group.pairs().collect::<Vec<_>>());
+    assert!(group.contained_members().is_empty());
+    assert_eq!(1, group.overlapping_pairs());
+    assert_eq!(None, group.intersection());
+
+    let group = assignment("2-8,3-7,4-6,5-5");
+    assert_eq!(vec![1, 2, 3], group.contained_members());
+    assert_eq!(6, group.overlapping_pairs());
+    assert_eq!(Some("5-5".parse().unwrap()), group.intersection());
+  }
+
+  #[test]
+  fn it_handles_equal_and_single_members() {
+    assert_eq!(vec![0, 1], assignment("3-5,3-5").contained_members());
+    assert!(assignment("3-5").contained_members().is_empty());
+    assert_eq!(Some("3-5".parse().unwrap()), assignment("3-5").intersection());
+  }
+}
@@ -0,0 +1,198 @@
+use crate::{Assignment, SectionRange};
+
+// Every range of every assignment, kept sorted by start so that merging and
+// sweeping are a single pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+  ranges: Vec<SectionRange>,
+}
+
+impl IntervalSet {
+  pub fn new() -> IntervalSet {
+    IntervalSet::default()
+  }
+
+  // Sorts once rather than inserting each range in place.
+  pub fn from_assignments(assignments: &[Assignment]) -> IntervalSet {
+    let mut ranges: Vec<SectionRange> =
+      assignments.iter().flat_map(|assignment| assignment.ranges.iter().copied()).collect();
+    ranges.sort_unstable();
+
+    IntervalSet { ranges }
+  }
+
+  pub fn insert(&mut self, range: SectionRange) {
+    let at = self.ranges.partition_point(|r| r <= &range);
+    self.ranges.insert(at, range);
+  }
+
+  // Number of inserted ranges, overlapping ones included.
+  pub fn len(&self) -> usize {
+    self.ranges.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.ranges.is_empty()
+  }
+
+  // Disjoint spans covering exactly the sections of the set, in order.
+  pub fn merged(&self) -> Vec<SectionRange> {
+    let mut spans: Vec<SectionRange> = vec![];
+
+    for range in &self.ranges {
+      if let Some(last) = spans.last_mut() {
+        if let Some(union) = last.union(range) {
+          *last = union;
+          continue;
+        }
+      }
+
+      spans.push(*range);
+    }
+
+    spans
+  }
+
+  // Sections between the first and the last covered one that nobody covers.
+  pub fn gaps(&self) -> Vec<SectionRange> {
+    self
+      .merged()
+      .windows(2)
+      .filter_map(|pair| SectionRange::new(pair[0].end + 1, pair[1].start - 1))
+      .collect()
+  }
+
+  // Sweeps the set from left to right and returns every maximal span where
+  // the number of covering ranges stays the same. Uncovered spans are left
+  // out.
+  pub fn coverage(&self) -> Vec<(SectionRange, usize)> {
+    // a range covers [start, end + 1), i128 keeps `end + 1` from overflowing
+    let mut events: Vec<(i128, isize)> = self
+      .ranges
+      .iter()
+      .flat_map(|r| [(r.start as i128, 1), (r.end as i128 + 1, -1)])
+      .collect();
+    events.sort();
+
+    let mut coverage: Vec<(SectionRange, usize)> = vec![];
+    let mut depth: isize = 0;
+
+    for (i, &(position, delta)) in events.iter().enumerate() {
+      depth += delta;
+
+      let Some(&(next, _)) = events.get(i + 1) else { break };
+      if depth == 0 || next == position {
+        continue;
+      }
+
+      let span = SectionRange { start: position as isize, end: (next - 1) as isize };
+      match coverage.last_mut() {
+        Some((last, last_depth)) if *last_depth == depth as usize && last.end + 1 == span.start => {
+          last.end = span.end
+        }
+        _ => coverage.push((span, depth as usize)),
+      }
+    }
+
+    coverage
+  }
+
+  // The highest number of ranges covering one section, with every span
+  // reaching it.
+  pub fn max_depth(&self) -> Option<(usize, Vec<SectionRange>)> {
+    let coverage = self.coverage();
+    let max = coverage.iter().map(|(_, depth)| *depth).max()?;
+
+    let spans = coverage
+      .into_iter()
+      .filter(|(_, depth)| *depth == max)
+      .map(|(span, _)| span)
+      .collect();
+
+    Some((max, spans))
+  }
+
+  // Number of ranges covering `section`.
+  pub fn depth_at(&self, section: isize) -> usize {
+    self.ranges[..self.ranges.partition_point(|r| r.start <= section)]
+      .iter()
+      .filter(|r| r.contains(section))
+      .count()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parse;
+
+  fn range(input: &str) -> SectionRange {
+    input.parse().unwrap()
+  }
+
+  fn example() -> IntervalSet {
+    IntervalSet::from_assignments(&parse("2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8").unwrap())
+  }
+
+  #[test]
+  fn it_merges_and_lists_gaps() {
+    let mut set = IntervalSet::new();
+    set.insert(range("10-12"));
+    set.insert(range("1-3"));
+    set.insert(range("4-5"));
+    set.insert(range("2-4"));
+
+    assert_eq!(vec![range("1-5"), range("10-12")], set.merged());
+    assert_eq!(vec![range("6-9")], set.gaps());
+    assert_eq!(vec![range("2-9")], example().merged());
+    assert!(example().gaps().is_empty());
+  }
+
+  #[test]
+  fn it_sweeps_coverage() {
+    let mut set = IntervalSet::new();
+    set.insert(range("1-4"));
+    set.insert(range("3-6"));
+    set.insert(range("9-9"));
+
+    assert_eq!(
+      vec![(range("1-2"), 1), (range("3-4"), 2), (range("5-6"), 1), (range("9-9"), 1)],
+      set.coverage()
+    );
+    assert_eq!(Some((2, vec![range("3-4")])), set.max_depth());
+    assert_eq!(None, IntervalSet::new().max_depth());
+  }
+
+  #[test]
+  fn it_answers_point_queries() {
+    let set = example();
+
+    assert_eq!(12, set.len());
+
+    // sorting them all at once agrees with inserting them one by one
+    let mut inserted = IntervalSet::new();
+    for input in ["6-6", "2-8", "4-6", "2-3", "3-7", "7-9", "2-4", "6-8", "4-5", "2-6", "5-7", "4-8"] {
+      inserted.insert(range(input));
+    }
+    assert_eq!(inserted, set);
+    assert_eq!(0, set.depth_at(1));
+    assert_eq!(4, set.depth_at(2));
+    assert_eq!(8, set.depth_at(6));
+    assert_eq!(Some((8, vec![range("6-6")])), set.max_depth());
+  }
+
+  #[test]
+  fn it_handles_extreme_bounds() {
+    let mut set = IntervalSet::new();
+    set.insert(SectionRange { start: isize::MAX - 1, end: isize::MAX });
+    set.insert(SectionRange { start: isize::MIN, end: isize::MIN + 1 });
+
+    assert_eq!(2, set.coverage().len());
+    assert_eq!(1, set.gaps().len());
+  }
+}
@@ -1,14 +1,33 @@
+use common::{Answer, Result, Solution};
+
+pub use assignment::Assignment;
+pub use interval_set::IntervalSet;
+pub use range::SectionRange;
+
+mod assignment;
+mod interval_set;
+mod range;
 
 // 2-8,3-7
-pub fn exec(input: &String, is_overlap: fn(Vec<isize>, Vec<isize>) -> bool) -> isize {
+// 2-4,6-8,3-5
+pub fn parse(input: &str) -> Result<Vec<Assignment>> {
+  input.lines().enumerate().map(|(i, line)| {
+    let ranges = line
+      .split(',')
+      .map(|range| range.parse::<SectionRange>().map_err(|e| e.offset(i + 1, line, range)))
+      .collect::<Result<Vec<SectionRange>>>()?;
+
+    Ok(Assignment { ranges })
+  }).collect()
+}
+
+// Counts the assignments where at least one pair of members matches
+// `is_overlap`, which for the puzzle's pairs is the pair itself.
+pub fn exec(assignments: &[Assignment], is_overlap: fn(&SectionRange, &SectionRange) -> bool) -> isize {
   let mut result: isize = 0;
 
-  input.lines().for_each(|line| {
-    let pair: Vec<&str> = line.split(',').collect();
-  
-    let item1: Vec<isize> = construct_vec(pair[0]);
-    let item2: Vec<isize> = construct_vec(pair[1]);
-    if is_overlap(item1, item2) {
+  assignments.iter().for_each(|assignment| {
+    if assignment.pairs().any(|(i, j)| is_overlap(&assignment.ranges[i], &assignment.ranges[j])) {
       result += 1;
     }
   });
@@ -16,82 +35,97 @@ pub fn exec(input: &String, is_overlap: fn(Vec<isize>, Vec<isize>) -> bool) -> i
   result
 }
 
-// If all elements within a section are present in 
-// the corresponding section of its pair, it is considered overlapping.
-pub fn is_overlap1(item1: Vec<isize>, item2: Vec<isize>) -> bool {
-  if item1.len() > item2.len() {
-    for val in item2 {
-      if !item1.contains(&val) {
-        return false;
-      }
-    }
+// If all sections of a range are present in 
+// the corresponding range of its pair, it is considered overlapping.
+pub fn is_overlap1(item1: &SectionRange, item2: &SectionRange) -> bool {
+  item1.contains_range(item2) || item2.contains_range(item1)
+}
 
-    true
-  } else {
-    for val in item1 {
-      if !item2.contains(&val) {
-        return false;
-      }
-    }
-    
-    true
-  }
+// If a section of a range is present in 
+// the corresponding range of its pair, it is considered overlapping.
+pub fn is_overlap2(item1: &SectionRange, item2: &SectionRange) -> bool {
+  item1.overlaps(item2)
 }
 
-// If an element within a section are present in 
-// the corresponding section of its pair, it is considered overlapping.
-pub fn is_overlap2(item1: Vec<isize>, item2: Vec<isize>) -> bool {
-  if item1.len() > item2.len() {
-    for val in item2 {
-      if item1.contains(&val) {
-        return true;
-      }
-    }
+pub struct Day4;
 
-    false
-  } else {
-    for val in item1 {
-      if item2.contains(&val) {
-        return true;
-      }
-    }
-    
-    false
+impl Solution for Day4 {
+  type Input<'a> = Vec<Assignment>;
+
+  fn parse(input: &str) -> Result<Vec<Assignment>> {
+    parse(input)
   }
-}
 
-fn construct_vec(input: &str) -> Vec<isize> {
-  let range_str: Vec<&str> = input.split('-').collect();
-  let lower: isize = range_str[0].parse().unwrap();
-  let upper: isize = range_str[1].parse().unwrap();
-  let result: Vec<isize> = (lower..=upper).collect();
-  result
+  fn part1(assignments: &Vec<Assignment>) -> Answer {
+    exec(assignments, is_overlap1).into()
+  }
+
+  fn part2(assignments: &Vec<Assignment>) -> Answer {
+    exec(assignments, is_overlap2).into()
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
-  #[test]
-  fn it_produces_vec_from_range_syntax() {
-    assert_eq!(vec![2,3,4,5,6,7,8], construct_vec("2-8"));
-    assert_eq!(vec![3,4,5,6,7], construct_vec("3-7"));
+  fn range(input: &str) -> SectionRange {
+    input.parse().unwrap()
   }
 
   #[test]
   fn it_checks_overlap1() {
-    assert!(is_overlap1(vec![2,3,4,5,6,7,8], vec![3,4,5,6,7]));
-    assert!(is_overlap1(vec![6], vec![4,5,6]));
-    assert!(!is_overlap1(vec![2,3], vec![4,5]));
+    assert!(is_overlap1(&range("2-8"), &range("3-7")));
+    assert!(is_overlap1(&range("6-6"), &range("4-6")));
+    assert!(!is_overlap1(&range("2-3"), &range("4-5")));
   }
 
   #[test]
   fn it_checks_overlap2() {
-    assert!(!is_overlap2(construct_vec("2-4"), construct_vec("6-8")));
-    assert!(!is_overlap2(construct_vec("2-3"), construct_vec("4-5")));
+    assert!(!is_overlap2(&range("2-4"), &range("6-8")));
+    assert!(!is_overlap2(&range("2-3"), &range("4-5")));
     
-    assert!(is_overlap2(construct_vec("5-7"), construct_vec("7-9")));
-    assert!(is_overlap2(construct_vec("2-8"), construct_vec("3-7")));
-    assert!(is_overlap2(construct_vec("6-6"), construct_vec("4-6")));
+    assert!(is_overlap2(&range("5-7"), &range("7-9")));
+    assert!(is_overlap2(&range("2-8"), &range("3-7")));
+    assert!(is_overlap2(&range("6-6"), &range("4-6")));
+  }
+
+  #[test]
+  fn it_counts_pairs() {
+    let assignments = parse("2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8").unwrap();
+
+    assert_eq!(2, exec(&assignments, is_overlap1));
+    assert_eq!(4, exec(&assignments, is_overlap2));
+  }
+
+  #[test]
+  fn it_handles_huge_and_negative_ranges() {
+    let assignments = parse("1-4000000000,2-3999999999\n-10--5,-6-0").unwrap();
+
+    assert_eq!(1, exec(&assignments, is_overlap1));
+    assert_eq!(2, exec(&assignments, is_overlap2));
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn it_counts_groups() {
+    let assignments = parse("2-4,6-8,3-5\n2-8,3-7,4-6\n1-1").unwrap();
+
+    assert_eq!(3, assignments[0].ranges.len());
+    assert_eq!(1, exec(&assignments, is_overlap1));
+    assert_eq!(2, exec(&assignments, is_overlap2));
+  }
+
+  #[test]
+  fn it_reports_bad_ranges() {
+    let error = parse("2-4,6-8\n2-3,4-x").unwrap_err();
+    assert_eq!((2, 7, "x"), (error.line, error.column, error.text.as_str()));
+
+    let error = parse("2-4,-").unwrap_err();
+    assert_eq!((1, 5, "-"), (error.line, error.column, error.text.as_str()));
+  }
+}
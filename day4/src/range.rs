@@ -0,0 +1,126 @@
+use std::fmt;
+use std::str::FromStr;
+
+use common::ParseError;
+
+// An inclusive range of section ids, `2-8` covers sections 2 to 8.
+// Bounds may be negative (`-5--2`), every operation is O(1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SectionRange {
+  pub start: isize,
+  pub end: isize,
+}
+
+impl SectionRange {
+  // `None` when `start` is after `end`.
+  pub fn new(start: isize, end: isize) -> Option<SectionRange> {
+    (start <= end).then_some(SectionRange { start, end })
+  }
+
+  // Number of sections in the range.
+  pub fn sections(&self) -> usize {
+    self.end.abs_diff(self.start).saturating_add(1)
+  }
+
+  pub fn contains(&self, section: isize) -> bool {
+    self.start <= section && section <= self.end
+  }
+
+  // Every section of `other` is also in `self`.
+  pub fn contains_range(&self, other: &SectionRange) -> bool {
+    self.start <= other.start && other.end <= self.end
+  }
+
+  pub fn overlaps(&self, other: &SectionRange) -> bool {
+    self.start <= other.end && other.start <= self.end
+  }
+
+  pub fn intersection(&self, other: &SectionRange) -> Option<SectionRange> {
+    SectionRange::new(self.start.max(other.start), self.end.min(other.end))
+  }
+
+  // The single range covering both, `None` when a gap separates them.
+  // Adjacent ranges such as `2-4` and `5-7` merge into `2-7`.
+  pub fn union(&self, other: &SectionRange) -> Option<SectionRange> {
+    let (first, second) = if self.start <= other.start { (self, other) } else { (other, self) };
+
+    if second.start.saturating_sub(1) > first.end {
+      return None;
+    }
+
+    SectionRange::new(first.start, first.end.max(second.end))
+  }
+}
+
+impl fmt::Display for SectionRange {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}-{}", self.start, self.end)
+  }
+}
+
+impl FromStr for SectionRange {
+  type Err = ParseError;
+
+  fn from_str(input: &str) -> Result<SectionRange, ParseError> {
+    // the separator is the first `-` that follows a digit, so that a
+    // negative start like `-5-3` keeps its sign
+    let separator = input
+      .char_indices()
+      .skip(1)
+      .find(|&(i, c)| c == '-' && input[..i].ends_with(|p: char| p.is_ascii_digit()))
+      .map(|(i, _)| i)
+      .ok_or_else(|| ParseError::line(1, input, "expected a range like `2-8`"))?;
+
+    let (lower, upper) = (&input[..separator], &input[separator + 1..]);
+
+    let section = |s: &str| s.parse::<isize>()
+      .map_err(|_| ParseError::at(1, input, s, "invalid section number"));
+
+    let start = section(lower)?;
+    let end = section(upper)?;
+
+    SectionRange::new(start, end)
+      .ok_or_else(|| ParseError::line(1, input, "range starts after it ends"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn range(input: &str) -> SectionRange {
+    input.parse().unwrap()
+  }
+
+  #[test]
+  fn it_parses_ranges() {
+    assert_eq!(SectionRange { start: 2, end: 8 }, range("2-8"));
+    assert_eq!(SectionRange { start: -5, end: -2 }, range("-5--2"));
+    assert_eq!(SectionRange { start: 1, end: 4000000000 }, range("1-4000000000"));
+    assert_eq!(4000000000, range("1-4000000000").sections());
+
+    assert!("8-2".parse::<SectionRange>().is_err());
+    assert!("2".parse::<SectionRange>().is_err());
+    assert_eq!(3, "2-x".parse::<SectionRange>().unwrap_err().column);
+  }
+
+  #[test]
+  fn it_compares_ranges() {
+    assert!(range("2-8").contains_range(&range("3-7")));
+    assert!(!range("3-7").contains_range(&range("2-8")));
+    assert!(range("-3-3").contains(0));
+
+    assert!(range("5-7").overlaps(&range("7-9")));
+    assert!(!range("2-4").overlaps(&range("6-8")));
+  }
+
+  #[test]
+  fn it_combines_ranges() {
+    assert_eq!(Some(range("7-7")), range("5-7").intersection(&range("7-9")));
+    assert_eq!(None, range("2-3").intersection(&range("4-5")));
+
+    assert_eq!(Some(range("2-5")), range("2-3").union(&range("4-5")));
+    assert_eq!(Some(range("2-8")), range("3-7").union(&range("2-8")));
+    assert_eq!(None, range("2-3").union(&range("5-6")));
+  }
+}
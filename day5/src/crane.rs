@@ -0,0 +1,96 @@
+// How a crane carries the crates of one `move N from A to B`: the move is
+// split into successive lifts, and every lift puts its crates down in the
+// order they were stacked.
+pub trait Crane {
+  fn name(&self) -> String;
+
+  // Sizes of the lifts carrying `len` crates, top of the stack first.
+  fn lifts(&self, len: usize) -> Vec<usize>;
+}
+
+// Moves one crate at a time, so the moved crates end up reversed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+  fn name(&self) -> String {
+    "CrateMover 9000".to_string()
+  }
+
+  fn lifts(&self, len: usize) -> Vec<usize> {
+    vec![1; len]
+  }
+}
+
+// Moves all the crates at once, keeping their order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+  fn name(&self) -> String {
+    "CrateMover 9001".to_string()
+  }
+
+  fn lifts(&self, len: usize) -> Vec<usize> {
+    if len == 0 {
+      return vec![];
+    }
+
+    vec![len]
+  }
+}
+
+// Lifts at most `capacity` crates at once, bigger moves are split into
+// chunks taken from the top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitedCrane {
+  pub capacity: usize,
+}
+
+impl Crane for LimitedCrane {
+  fn name(&self) -> String {
+    format!("crane lifting {}", self.capacity)
+  }
+
+  fn lifts(&self, len: usize) -> Vec<usize> {
+    let capacity = self.capacity.max(1);
+
+    (0..len)
+      .step_by(capacity)
+      .map(|lifted| capacity.min(len - lifted))
+      .collect()
+  }
+}
+
+// `9000`, `9001`, or the capacity of a custom crane.
+pub fn crane_from_str(model: &str) -> Option<Box<dyn Crane>> {
+  match model {
+    "9000" => Some(Box::new(CrateMover9000)),
+    "9001" => Some(Box::new(CrateMover9001)),
+    capacity => match capacity.parse::<usize>() {
+      Ok(capacity) if capacity > 0 => Some(Box::new(LimitedCrane { capacity })),
+      _ => None,
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_split_moves_into_lifts() {
+    assert_eq!(vec![1, 1, 1], CrateMover9000.lifts(3));
+    assert_eq!(vec![3], CrateMover9001.lifts(3));
+    assert_eq!(vec![2, 2, 1], LimitedCrane { capacity: 2 }.lifts(5));
+    assert!(CrateMover9001.lifts(0).is_empty());
+  }
+
+  #[test]
+  fn should_pick_cranes_by_model() {
+    assert_eq!("CrateMover 9000", crane_from_str("9000").unwrap().name());
+    assert_eq!("crane lifting 4", crane_from_str("4").unwrap().name());
+    assert!(crane_from_str("0").is_none());
+    assert!(crane_from_str("big").is_none());
+  }
+}
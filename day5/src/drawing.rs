@@ -0,0 +1,170 @@
+use nom::{
+  IResult,
+  sequence::{delimited, preceded},
+  character::complete::{char, space0},
+  bytes::complete::is_not,
+  combinator::consumed,
+  multi::many0,
+};
+use std::ops::Range;
+
+use common::{ParseError, Result};
+
+use crate::{Crate, Stacks};
+
+// The stacks drawn above the footer numbering them. Each crate belongs to
+// the stack whose number sits under it, so labels may have any width.
+pub(crate) fn parse_drawing(drawing: &str) -> Result<Stacks> {
+  let lines = drawing.split('\n').collect::<Vec<&str>>();
+
+  let Some(footer_idx) = lines.iter().rposition(|line| !line.trim().is_empty()) else {
+    return Err(ParseError::new(1, 1, "", "expected a drawing of the stacks"));
+  };
+  let footer = lines[footer_idx];
+  if !is_footer(footer) {
+    return Err(ParseError::line(footer_idx + 1, footer, "expected the row numbering the stacks"));
+  }
+
+  let mut stack_columns = vec![];
+  for (i, number) in footer.split_whitespace().enumerate() {
+    if number.parse::<usize>().ok() != Some(i + 1) {
+      return Err(ParseError::at(footer_idx + 1, footer, number, format!("expected stack {}", i + 1)));
+    }
+    stack_columns.push(columns(footer, number));
+  }
+
+  let mut stacks = Stacks::new(stack_columns.len());
+
+  // bottom row first
+  for (i, value_line) in lines[..footer_idx].iter().enumerate().rev() {
+    let (rest, tokens) = parse_row(value_line)
+      .map_err(|e| crate::nom_error(i + 1, value_line, e, "expected `[label]` or an empty slot"))?;
+    if !rest.trim().is_empty() {
+      return Err(ParseError::at(i + 1, value_line, rest, "expected `[label]` or an empty slot"));
+    }
+
+    let level = footer_idx - 1 - i;
+    for (token, label) in tokens {
+      let span = columns(value_line, token);
+      let below: Vec<usize> = stack_columns.iter()
+        .enumerate()
+        .filter(|(_, column)| column.start < span.end && span.start < column.end)
+        .map(|(k, _)| k + 1)
+        .collect();
+
+      let [stack] = below[..] else {
+        return Err(ParseError::at(i + 1, value_line, token, "crate is not above a single numbered stack"));
+      };
+
+      let height = stacks.stack(stack).map_or(0, <[Crate]>::len);
+      if height > level {
+        return Err(ParseError::at(i + 1, value_line, token, format!("two crates in one slot of stack {stack}")));
+      }
+      if height < level {
+        return Err(ParseError::at(i + 1, value_line, token, format!("crate {token} floats above stack {stack}")));
+      }
+
+      stacks.push(stack, Crate::new(label));
+    }
+  }
+
+  Ok(stacks)
+}
+
+// Draws the stacks so that `parse_drawing` reads them back: every column is
+// as wide as its widest crate and centered over its number.
+pub(crate) fn render(stacks: &Stacks) -> String {
+  let numbers: Vec<String> = (1..=stacks.len()).map(|k| k.to_string()).collect();
+  let columns: Vec<(&[Crate], usize)> = numbers.iter()
+    .enumerate()
+    .map(|(i, number)| {
+      let stack = stacks.stack(i + 1).unwrap_or_default();
+      let width = stack.iter()
+        .map(|item| item.label().chars().count() + 2)
+        .chain([number.len() + 2, 3])
+        .max()
+        .unwrap_or(3);
+      (stack, width)
+    })
+    .collect();
+
+  let height = columns.iter().map(|(stack, _)| stack.len()).max().unwrap_or(0);
+
+  let mut lines: Vec<String> = (0..height).rev().map(|level| {
+    columns.iter()
+      .map(|(stack, width)| match stack.get(level) {
+        Some(item) => format!("{:^width$}", item.to_string()),
+        None => " ".repeat(*width),
+      })
+      .collect::<Vec<String>>()
+      .join(" ")
+  }).collect();
+
+  let footer: Vec<String> = numbers.iter()
+    .zip(&columns)
+    .map(|(number, (_, width))| format!("{number:^width$}"))
+    .collect();
+  lines.push(footer.join(" "));
+
+  lines.join("\n")
+}
+
+fn is_footer(line: &str) -> bool {
+  !line.trim().is_empty() && line.chars().all(|c| c.is_ascii_digit() || c == ' ')
+}
+
+// Char columns covered by `fragment`, a slice of `line`.
+fn columns(line: &str, fragment: &str) -> Range<usize> {
+  let offset = fragment.as_ptr() as usize - line.as_ptr() as usize;
+  let start = line[..offset].chars().count();
+
+  start..start + fragment.chars().count()
+}
+
+// `[label]` crates of a row, each as the whole token and its label.
+fn parse_row(input: &str) -> IResult<&str, Vec<(&str, &str)>> {
+  let label = delimited(char('['), is_not("]"), char(']'));
+
+  many0(preceded(space0, consumed(label)))(input)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn labels(row: &str) -> Vec<&str> {
+    parse_row(row).unwrap().1.into_iter().map(|(_, label)| label).collect()
+  }
+
+  #[test]
+  fn should_parse_rows() {
+    assert_eq!(vec!["Z", "M", "P"], labels("[Z] [M] [P]"));
+    assert_eq!(vec!["N", "C"], labels("[N] [C]    "));
+    assert_eq!(vec!["D"], labels("    [D]    "));
+    assert_eq!(vec!["10", "Z9", "é"], labels("[10] [Z9] [é]"));
+  }
+
+  #[test]
+  fn should_place_wide_labels_by_footer_column() {
+    let drawing = " [é]                                        [Z9]
+ [A]  [B]  [C]  [D]  [E]  [F]  [G]  [H]  [I]  [10]
+  1    2    3    4    5    6    7    8    9    10 ";
+
+    let stacks = parse_drawing(drawing).unwrap();
+    assert_eq!(10, stacks.len());
+    assert_eq!("éBCDEFGHIZ9", stacks.tops());
+    assert_eq!(Some(&[Crate::new("10"), Crate::new("Z9")][..]), stacks.stack(10));
+
+    let redrawn = render(&stacks);
+    assert_eq!("[é]                                 [Z9]
+[A] [B] [C] [D] [E] [F] [G] [H] [I] [10]
+ 1   2   3   4   5   6   7   8   9   10 ", redrawn);
+    assert_eq!(stacks, parse_drawing(&redrawn).unwrap());
+  }
+
+  #[test]
+  fn should_reject_crates_between_stacks() {
+    let error = parse_drawing("  [A]  \n 1   2 ").unwrap_err();
+    assert_eq!((1, 3, "crate is not above a single numbered stack"), (error.line, error.column, error.message.as_str()));
+  }
+}
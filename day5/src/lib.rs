@@ -1,143 +1,214 @@
 use nom::{
   IResult,
-  sequence::{delimited, preceded, tuple},
-  character::complete::{char, alpha0, digit0, space0},
+  sequence::{delimited, preceded},
+  character::complete::{digit1, space0},
   bytes::complete::tag,
-  multi::separated_list0,
-  branch::alt, combinator::{value}, error::{Error},
+  error::{Error},
 };
-use std::{collections::HashMap, fmt::Write};
+use std::fmt;
+
+use common::{Answer, ParseError, Result, Solution};
+
+pub use crane::{crane_from_str, Crane, CrateMover9000, CrateMover9001, LimitedCrane};
+pub use script::{parse_script, Command, RunError, Script, ScriptError, Statement};
+pub use solver::{solve, SolveError, Target};
+pub use stacks::{Crate, MoveError, Stacks};
+pub use stream::{run_stream, Progress, StreamError};
+pub use trace::{render_trace, Step};
+
+mod crane;
+mod drawing;
+mod script;
+mod solver;
+mod stacks;
+mod stream;
+mod trace;
+
+pub fn exec(buf: String, crane: &dyn Crane) -> Result<String> {
+  Ok(parse(&buf)?.run(crane))
+}
 
-pub fn exec(buf: String) -> String {
-  let buf = buf.split("\n\n").collect::<Vec<&str>>();
+// move 1 from 2 to 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+  pub len: usize,
+  pub src: usize,
+  pub dst: usize,
+}
 
-  let crate_stack_raw = buf[0].split('\n').collect::<Vec<&str>>();
+impl fmt::Display for Move {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "move {} from {} to {}", self.len, self.src, self.dst)
+  }
+}
 
-  let mut crates_store: Store = Store::new();
+// The starting stacks and the rearrangement procedure.
+#[derive(Debug)]
+pub struct Procedure {
+  stacks: Stacks,
+  moves: Vec<Move>,
+}
 
-  // feed the store
-  for value_line in crate_stack_raw.into_iter() {
-    let elems = parse_elements(value_line).unwrap().1;
-    crates_store.insert_from_vec(elems);
+impl Procedure {
+  // The stacks before any move.
+  pub fn stacks(&self) -> &Stacks {
+    &self.stacks
   }
 
-  let cmd_iter = buf[1].lines();
-  for line in cmd_iter {
-    crates_store.move_crate(line);
+  // Every move done by `crane`, with the stacks after each of them.
+  pub fn trace(&self, crane: &dyn Crane) -> Vec<Step> {
+    let mut stacks = self.stacks.clone();
+
+    self.moves.iter().enumerate().map(|(i, mv)| {
+      let moved = stacks.apply(mv, crane).expect("moves are checked by parse");
+
+      Step {
+        number: i + 1,
+        mv: *mv,
+        moved,
+        stacks: stacks.clone(),
+      }
+    }).collect()
   }
 
-  let mut result = String::new();
-  
-  let number_of_crates = crates_store.0.len();
-  for k in 1..=number_of_crates {
-    let crates = crates_store.0.get(&k).expect("fail to get crates");
-    let crate_val = crates[crates.len()-1]; 
-    
-    result.write_str(crate_val);
+  // The stacks once all the moves are done by `crane`.
+  pub fn finish(&self, crane: &dyn Crane) -> Stacks {
+    let mut stacks = self.stacks.clone();
+
+    for mv in self.moves.iter() {
+      stacks.apply(mv, crane).expect("moves are checked by parse");
+    }
+
+    stacks
   }
 
-  result
+  // Top crate of every stack once all the moves are done by `crane`.
+  pub fn run(&self, crane: &dyn Crane) -> String {
+    self.finish(crane).tops()
+  }
 }
 
-#[derive(Debug)]
-pub struct Store<'a>(HashMap<usize, Vec<&'a str>>);
+// Moves are replayed on the stacks while parsing: every crane moves the same
+// number of crates, so a move that fails here fails for all of them.
+pub fn parse(buf: &str) -> Result<Procedure> {
+  let (stacks, cmds, first_cmd_line) = split_input(buf)?;
 
-impl<'a> Store<'a> {
-  fn new() -> Store<'a> {
-    let kv: HashMap<usize, Vec<&str>> = HashMap::new();
-    Store(kv)
-  }
+  let mut replay = stacks.clone();
+  let mut moves = vec![];
 
-  fn insert_from_vec(&mut self, elems: Vec<&'a str>) {
-    for (i, val) in elems.into_iter().enumerate() {
-      let k = i+1;
-      
-      if !val.is_empty() {
-        self.0.entry(k).or_insert(Vec::new()).insert(0, val);
-      }      
+  for (i, line) in cmds.lines().enumerate() {
+    if line.trim().is_empty() {
+      continue;
     }
+
+    let line_no = first_cmd_line + i;
+    let mv = parse_move(line).map_err(|e| ParseError { line: line_no, ..e })?;
+    replay.apply(&mv, &CrateMover9001)
+      .map_err(|e| ParseError::line(line_no, line, e.to_string()))?;
+
+    moves.push(mv);
   }
 
-  // move 1 from 2 to 1
-  // len: 1
-  // src: 2
-  // dst: 1
-  fn move_crate(&mut self, cmd: &str) {
-    let (_, (len, src, dst)) = tuple(
-      (
-        parse_crate_len, 
-        parse_src_crate, 
-        parse_dst_crate
-      )
-    )(cmd).expect("fail to parse len, src, and dst values");    
-  
-    let converted_len = len
-      .parse::<usize>()
-      .expect("fail converted len");
+  Ok(Procedure {
+    stacks,
+    moves,
+  })
+}
 
-    let converted_src = src
-      .parse::<usize>()
-      .expect("fail to convert src value");
+// The stacks drawn above the blank line, then the commands below it and
+// the line they start on. Lines may end in `\r\n`, as `run_stream` allows.
+fn split_input(buf: &str) -> Result<(Stacks, &str, usize)> {
+  let mut drawing = vec![];
+  let mut rest = buf;
+
+  loop {
+    let Some((line, after)) = rest.split_once('\n') else {
+      let line = buf.lines().count().max(1);
+      return Err(ParseError::new(line, 1, "", "expected a blank line between the drawing and the moves"));
+    };
+    rest = after;
+
+    let line = line.trim_end_matches('\r');
+    if line.is_empty() {
+      break;
+    }
+    drawing.push(line);
+  }
+
+  let stacks = drawing::parse_drawing(&drawing.join("\n"))?;
+  Ok((stacks, rest, drawing.len() + 2))
+}
 
-    let converted_dst = dst
-      .parse::<usize>()
-      .expect("fail to convert dst value");
+pub struct Day5;
 
-    let store = &mut self.0;
+impl Solution for Day5 {
+  type Input<'a> = Procedure;
 
-    let src_elems = store
-      .get_mut(&converted_src)
-      .expect("fail to get a map value");
+  fn parse(input: &str) -> Result<Procedure> {
+    parse(input)
+  }
 
-    let mut moved_crates = src_elems.split_off(src_elems.len() - converted_len);
-    
-    // moved_crates.reverse();
+  fn part1(procedure: &Procedure) -> Answer {
+    procedure.run(&CrateMover9000).into()
+  }
 
-    store.entry(converted_dst)
-      .or_insert(Vec::new())
-      .append(&mut moved_crates);
+  fn part2(procedure: &Procedure) -> Answer {
+    procedure.run(&CrateMover9001).into()
+  }
+}
 
+// Maps a nom failure on `line` to the position where parsing stopped.
+fn nom_error(line_no: usize, line: &str, e: nom::Err<Error<&str>>, message: &str) -> ParseError {
+  match e {
+    nom::Err::Error(e) | nom::Err::Failure(e) => ParseError::at(line_no, line, e.input, message),
+    nom::Err::Incomplete(_) => ParseError::line(line_no, line, message),
   }
 }
 
-fn parse_elements(input: &str) -> IResult<&str, Vec<&str>> {
-  let get_alpha_in_bracket = delimited(
-    char('['), 
-    alpha0,
-    char(']')
-  );
-
-  let mark_it_is_empty = value("", tag("   "));
-  
-  let mut parser = separated_list0(
-    tag(" "), 
-    alt((
-      mark_it_is_empty,
-      get_alpha_in_bracket,
-    )),
-  );
-
-  parser(input)
+// move 1 from 2 to 1
+// len: 1
+// src: 2
+// dst: 1
+fn parse_move(line: &str) -> Result<Move> {
+  let (rest, len) = parse_crate_len(line)
+    .map_err(|e| nom_error(1, line, e, "expected `move N`"))?;
+  let (rest, src) = parse_src_crate(rest)
+    .map_err(|e| nom_error(1, line, e, "expected `from N`"))?;
+  let (rest, dst) = parse_dst_crate(rest)
+    .map_err(|e| nom_error(1, line, e, "expected `to N`"))?;
+
+  if !rest.trim().is_empty() {
+    return Err(ParseError::at(1, line, rest, "unexpected text after the move"));
+  }
+
+  let number = |n: &str| n.parse::<usize>()
+    .map_err(|_| ParseError::at(1, line, n, "number out of range"));
+
+  Ok(Move {
+    len: number(len)?,
+    src: number(src)?,
+    dst: number(dst)?,
+  })
 }
 
 fn parse_crate_len(input: &str) -> IResult<&str, &str, Error<&str>> {
   preceded(
     tag("move"),
-    delimited(space0, digit0, space0)
+    delimited(space0, digit1, space0)
   )(input)
 }
 
 fn parse_src_crate(input: &str) -> IResult<&str, &str, Error<&str>> {
   preceded(
     tag("from"),
-    delimited(space0, digit0, space0)
+    delimited(space0, digit1, space0)
   )(input)
 }
 
 fn parse_dst_crate(input: &str) -> IResult<&str, &str, Error<&str>> {
   preceded(
     tag("to"),
-    preceded(space0, digit0)
+    preceded(space0, digit1)
   )(input)
 }
 
@@ -145,16 +216,8 @@ fn parse_dst_crate(input: &str) -> IResult<&str, &str, Error<&str>> {
 mod tests {
   use super::*;
 
-  #[test]
-  fn should_parse_elements() {
-    let (_, result) = parse_elements("[Z] [M] [P]").unwrap();
-    assert_eq!(vec!["Z", "M", "P"], result);
-
-    let (_, result) = parse_elements("[N] [C]    ").unwrap();
-    assert_eq!(vec!["N", "C", ""], result);
-  
-    let (_, result) = parse_elements("    [D]    ").unwrap();
-    assert_eq!(vec!["", "D", ""], result);
+  fn labels(crates: &[Crate]) -> Vec<&str> {
+    crates.iter().map(Crate::label).collect()
   }
 
   #[test]
@@ -169,8 +232,11 @@ move 3 from 1 to 3
 move 2 from 2 to 1
 move 1 from 1 to 2";
 
-    // assert_eq!("CMZ", exec(String::from(payload)));
-    assert_eq!("MCD", exec(String::from(payload)));
+    assert_eq!("CMZ", exec(String::from(payload), &CrateMover9000).unwrap());
+    assert_eq!("MCD", exec(String::from(payload), &CrateMover9001).unwrap());
+    assert_eq!("MCD", exec(String::from(payload), &LimitedCrane { capacity: 3 }).unwrap());
+    assert_eq!("CMZ", exec(String::from(payload), &LimitedCrane { capacity: 1 }).unwrap());
+    assert_eq!("MCZ", exec(String::from(payload), &LimitedCrane { capacity: 2 }).unwrap());
 
   }
 
@@ -186,4 +252,65 @@ move 1 from 1 to 2";
     assert_eq!("1", result.1);
   }
 
-}
\ No newline at end of file
+  #[test]
+  fn should_report_bad_input() {
+    let error = parse("[A] [B]\n 1   2 \n\nmove 1 from 1 to 2\nmove 1 frm 2 to 1").unwrap_err();
+    assert_eq!((5, 8, "frm 2 to 1"), (error.line, error.column, error.text.as_str()));
+
+    let error = parse("[A] (B)\n 1   2 \n\nmove 1 from 1 to 2").unwrap_err();
+    assert_eq!((1, 4, " (B)"), (error.line, error.column, error.text.as_str()));
+
+    assert!(parse("[A]\n 1 \nmove 1 from 1 to 1").is_err());
+
+    let error = parse("[A]    \n 1   2 \n\nmove 1 from 1 to 2\nmove 2 from 2 to 1").unwrap_err();
+    assert_eq!((5, "cannot move 2 crates from stack 2, it holds 1"), (error.line, error.message.as_str()));
+
+    let error = parse("[A]\n 1 \n\nmove 1 from 1 to 4").unwrap_err();
+    assert_eq!((4, "there is no stack 4, the drawing numbers 1"), (error.line, error.message.as_str()));
+
+    let error = parse("    [B]\n[A]    \n 1   2 \n\n").unwrap_err();
+    assert_eq!((1, "crate [B] floats above stack 2"), (error.line, error.message.as_str()));
+
+    let error = parse("[A]\n 1   3 \n\n").unwrap_err();
+    assert_eq!((2, 6, "expected stack 2"), (error.line, error.column, error.message.as_str()));
+  }
+
+  #[test]
+  fn should_render_the_drawing() {
+    let payload = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1";
+
+    let procedure = parse(payload).unwrap();
+    assert_eq!(payload.split_once("\n\n").unwrap().0, procedure.stacks().to_string());
+
+    let steps = procedure.trace(&CrateMover9000);
+    assert_eq!(1, steps.len());
+    assert_eq!(vec!["D"], labels(&steps[0].moved));
+    assert_eq!("step 1: move 1 from 2 to 1 [D]
+[D]        
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 ", steps[0].to_string());
+  }
+
+  #[test]
+  fn should_trace_every_move() {
+    let procedure = parse("[A]        \n[B] [C] [D]\n 1   2   3 \n\nmove 2 from 1 to 3\nmove 3 from 3 to 2").unwrap();
+
+    let steps = procedure.trace(&CrateMover9000);
+    assert_eq!(vec!["A", "B"], labels(&steps[0].moved));
+    assert_eq!(vec!["B", "A", "D"], labels(&steps[1].moved));
+
+    let steps = procedure.trace(&CrateMover9001);
+    assert_eq!(vec!["B", "A"], labels(&steps[0].moved));
+    assert_eq!(vec!["D", "B", "A"], labels(&steps[1].moved));
+
+    let trace = render_trace(procedure.stacks(), &steps);
+    assert!(trace.starts_with("initial\n[A]        \n"));
+    assert!(trace.contains("\nstep 2: move 3 from 3 to 2 [D] [B] [A]\n"));
+  }
+}
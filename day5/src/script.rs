@@ -0,0 +1,415 @@
+use nom::{
+  IResult,
+  sequence::{delimited, pair, preceded, tuple},
+  character::complete::{alpha1, alphanumeric1, char, digit1, multispace1, not_line_ending, space0, space1},
+  bytes::complete::{is_not, tag},
+  multi::many0,
+  branch::alt,
+  combinator::{cut, fail, map, map_res, opt, recognize, verify},
+  error::{context, VerboseError, VerboseErrorKind},
+};
+use std::collections::HashMap;
+use std::fmt;
+
+use common::{ParseError, Result};
+
+use crate::{Crane, Move, MoveError, Stacks};
+
+type Res<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+// Nested calls allowed before a macro is taken as endlessly recursive.
+const MAX_CALL_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+  Move(Move),
+  // Back to the stacks before the last N commands that changed them.
+  Undo(usize),
+  Swap(usize, usize),
+  Reverse(usize),
+  If { stack: usize, label: String, then: Box<Command> },
+  Def { name: String, body: Vec<Command> },
+  Call(String),
+}
+
+// A top level command and the input line it starts on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+  pub line: usize,
+  pub command: Command,
+}
+
+// The starting stacks and the commands of a warehouse script.
+#[derive(Debug)]
+pub struct Script {
+  stacks: Stacks,
+  statements: Vec<Statement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError {
+  Move(MoveError),
+  NothingToUndo { wanted: usize, available: usize },
+  UnknownMacro(String),
+  TooDeep(String),
+}
+
+impl From<MoveError> for RunError {
+  fn from(e: MoveError) -> RunError {
+    RunError::Move(e)
+  }
+}
+
+impl fmt::Display for RunError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      RunError::Move(e) => write!(f, "{e}"),
+      RunError::NothingToUndo { wanted, available } => {
+        write!(f, "cannot undo {wanted} commands, only {available} changed the stacks")
+      }
+      RunError::UnknownMacro(name) => write!(f, "no macro named `{name}`"),
+      RunError::TooDeep(name) => write!(f, "`{name}` nests more than {MAX_CALL_DEPTH} macro calls"),
+    }
+  }
+}
+
+// A command that failed, with the line of the top level command running it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+  pub line: usize,
+  pub error: RunError,
+}
+
+impl fmt::Display for ScriptError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "line {}: {}", self.line, self.error)
+  }
+}
+
+impl Script {
+  pub fn stacks(&self) -> &Stacks {
+    &self.stacks
+  }
+
+  pub fn statements(&self) -> &[Statement] {
+    &self.statements
+  }
+
+  // Runs every command with `crane`. Each command that changes the stacks
+  // records the stacks it started from, which is what `undo` goes back to.
+  pub fn run(&self, crane: &dyn Crane) -> std::result::Result<Stacks, ScriptError> {
+    let mut machine = Machine {
+      stacks: self.stacks.clone(),
+      crane,
+      history: vec![],
+      macros: HashMap::new(),
+      depth: 0,
+    };
+
+    for statement in &self.statements {
+      let before = machine.stacks.clone();
+      machine.exec(&statement.command)
+        .map_err(|error| ScriptError { line: statement.line, error })?;
+
+      if !matches!(statement.command, Command::Undo(_)) && machine.stacks != before {
+        machine.history.push(before);
+      }
+    }
+
+    Ok(machine.stacks)
+  }
+}
+
+struct Machine<'c> {
+  stacks: Stacks,
+  crane: &'c dyn Crane,
+  history: Vec<Stacks>,
+  macros: HashMap<String, Vec<Command>>,
+  depth: usize,
+}
+
+impl Machine<'_> {
+  fn exec(&mut self, command: &Command) -> std::result::Result<(), RunError> {
+    match command {
+      Command::Move(mv) => {
+        self.stacks.apply(mv, self.crane)?;
+      }
+      Command::Undo(n) => {
+        let available = self.history.len();
+        if *n > available {
+          return Err(RunError::NothingToUndo { wanted: *n, available });
+        }
+
+        if let Some(stacks) = self.history.drain(available - n..).next() {
+          self.stacks = stacks;
+        }
+      }
+      Command::Swap(a, b) => self.stacks.swap(*a, *b)?,
+      Command::Reverse(stack) => self.stacks.reverse(*stack)?,
+      Command::If { stack, label, then } => {
+        if self.stacks.top(*stack)? == Some(label.as_str()) {
+          self.exec(then)?;
+        }
+      }
+      Command::Def { name, body } => {
+        self.macros.insert(name.clone(), body.clone());
+      }
+      Command::Call(name) => {
+        let body = self.macros.get(name).cloned().ok_or_else(|| RunError::UnknownMacro(name.clone()))?;
+        if self.depth == MAX_CALL_DEPTH {
+          return Err(RunError::TooDeep(name.clone()));
+        }
+
+        self.depth += 1;
+        let result = body.iter().try_for_each(|command| self.exec(command));
+        self.depth -= 1;
+        result?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+// A drawing, a blank line, then commands separated by new lines or `;`:
+//
+//   move N from A to B
+//   undo [N]
+//   swap A B
+//   reverse A
+//   if top A == X then COMMAND
+//   def NAME { COMMANDS }
+//   call NAME
+//
+// `#` starts a comment. `undo` only works at the top level.
+pub fn parse_script(buf: &str) -> Result<Script> {
+  let (stacks, text, first_cmd_line) = crate::split_input(buf)?;
+
+  let (rest, commands) = block(text, true).map_err(|e| syntax_error(text, first_cmd_line, e))?;
+  if !rest.is_empty() {
+    return Err(located(text, first_cmd_line, rest, "`}` without a `def`"));
+  }
+
+  let statements = commands.into_iter()
+    .map(|(start, command)| Statement {
+      line: first_cmd_line + text[..offset(text, start)].matches('\n').count(),
+      command,
+    })
+    .collect();
+
+  Ok(Script { stacks, statements })
+}
+
+// Commands up to a closing `}` or the end of the input, each with the text
+// it starts at.
+fn block(mut input: &str, top: bool) -> Res<'_, Vec<(&str, Command)>> {
+  let mut commands = vec![];
+
+  loop {
+    (input, _) = separators(input)?;
+    if input.is_empty() || input.starts_with('}') {
+      return Ok((input, commands));
+    }
+
+    let start = input;
+    let (rest, command) = if top { statement(input)? } else { command_in_def(input)? };
+
+    let (rest, _) = space0(rest)?;
+    if !(rest.is_empty() || rest.starts_with([';', '\n', '\r', '#', '}'])) {
+      let kind = VerboseErrorKind::Context("expected `;` or a new line after the command");
+      return Err(nom::Err::Failure(VerboseError { errors: vec![(rest, kind)] }));
+    }
+
+    commands.push((start, command));
+    input = rest;
+  }
+}
+
+fn statement(input: &str) -> Res<'_, Command> {
+  alt((undo, command))(input)
+}
+
+fn command_in_def(input: &str) -> Res<'_, Command> {
+  alt((
+    preceded(keyword("undo"), cut(context("`undo` only works at the top level", fail))),
+    command,
+  ))(input)
+}
+
+fn command(input: &str) -> Res<'_, Command> {
+  alt((move_crates, swap, reverse, if_top, def, call))(input)
+}
+
+fn move_crates(input: &str) -> Res<'_, Command> {
+  let (rest, (_, len, _, _, _, src, _, _, _, dst)) = preceded(
+    keyword("move"),
+    cut(context("expected `move N from A to B`", tuple((
+      space1, number, space1, keyword("from"), space1, number, space1, keyword("to"), space1, number,
+    )))),
+  )(input)?;
+
+  Ok((rest, Command::Move(Move { len, src, dst })))
+}
+
+fn undo(input: &str) -> Res<'_, Command> {
+  map(
+    preceded(keyword("undo"), cut(context("expected `undo [N]`", opt(preceded(space1, number))))),
+    |n| Command::Undo(n.unwrap_or(1)),
+  )(input)
+}
+
+fn swap(input: &str) -> Res<'_, Command> {
+  map(
+    preceded(keyword("swap"), cut(context("expected `swap A B`", pair(preceded(space1, number), preceded(space1, number))))),
+    |(a, b)| Command::Swap(a, b),
+  )(input)
+}
+
+fn reverse(input: &str) -> Res<'_, Command> {
+  map(
+    preceded(keyword("reverse"), cut(context("expected `reverse A`", preceded(space1, number)))),
+    Command::Reverse,
+  )(input)
+}
+
+fn if_top(input: &str) -> Res<'_, Command> {
+  let (rest, (_, _, _, stack, _, _, _, label, _, _, _, then)) = preceded(
+    keyword("if"),
+    cut(context("expected `if top A == X then COMMAND`", tuple((
+      space1, keyword("top"), space1, number, space0, tag("=="), space0, label, space1, keyword("then"), space1, command,
+    )))),
+  )(input)?;
+
+  Ok((rest, Command::If { stack, label: label.to_string(), then: Box::new(then) }))
+}
+
+fn def(input: &str) -> Res<'_, Command> {
+  let (rest, (_, name, _, _, body, _)) = preceded(
+    keyword("def"),
+    cut(context("expected `def NAME { COMMANDS }`", tuple((
+      space1, identifier, space0, char('{'), |i| block(i, false), char('}'),
+    )))),
+  )(input)?;
+
+  let body = body.into_iter().map(|(_, command)| command).collect();
+  Ok((rest, Command::Def { name: name.to_string(), body }))
+}
+
+fn call(input: &str) -> Res<'_, Command> {
+  map(
+    preceded(keyword("call"), cut(context("expected `call NAME`", preceded(space1, identifier)))),
+    |name| Command::Call(name.to_string()),
+  )(input)
+}
+
+fn keyword<'a>(word: &'static str) -> impl FnMut(&'a str) -> Res<'a, &'a str> {
+  verify(identifier, move |found: &str| found == word)
+}
+
+fn identifier(input: &str) -> Res<'_, &str> {
+  recognize(pair(alt((alpha1, tag("_"))), many0(alt((alphanumeric1, tag("_"))))))(input)
+}
+
+fn number(input: &str) -> Res<'_, usize> {
+  map_res(digit1, str::parse)(input)
+}
+
+// A crate label, bare or in brackets.
+fn label(input: &str) -> Res<'_, &str> {
+  alt((delimited(char('['), is_not("]"), char(']')), is_not(" \t\r\n;{}#")))(input)
+}
+
+// White space, `;` and comments between commands.
+fn separators(input: &str) -> Res<'_, Vec<&str>> {
+  many0(alt((multispace1, tag(";"), recognize(pair(char('#'), not_line_ending)))))(input)
+}
+
+fn offset(text: &str, fragment: &str) -> usize {
+  fragment.as_ptr() as usize - text.as_ptr() as usize
+}
+
+// Points at `at`, a slice of the commands `text` starting on `first_line`.
+fn located(text: &str, first_line: usize, at: &str, message: &str) -> ParseError {
+  let offset = offset(text, at);
+  let start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+  let end = text[offset..].find('\n').map_or(text.len(), |i| offset + i);
+  let line = &text[start..end];
+
+  ParseError::at(first_line + text[..offset].matches('\n').count(), line, &text[offset..end], message)
+}
+
+// The deepest failure position, described by the innermost command it was in.
+fn syntax_error(text: &str, first_line: usize, e: nom::Err<VerboseError<&str>>) -> ParseError {
+  let (at, message) = match &e {
+    nom::Err::Error(e) | nom::Err::Failure(e) => {
+      let at = e.errors.first().map_or(&text[text.len()..], |(at, _)| *at);
+      let message = e.errors.iter()
+        .find_map(|(_, kind)| match kind {
+          VerboseErrorKind::Context(context) => Some(*context),
+          _ => None,
+        })
+        .unwrap_or("expected a command");
+      (at, message)
+    }
+    nom::Err::Incomplete(_) => (&text[text.len()..], "expected a command"),
+  };
+
+  located(text, first_line, at, message)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{CrateMover9000, CrateMover9001};
+
+  const DRAWING: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\n";
+
+  fn run(commands: &str) -> std::result::Result<String, ScriptError> {
+    let script = parse_script(&format!("{DRAWING}{commands}")).unwrap();
+    script.run(&CrateMover9001).map(|stacks| stacks.tops())
+  }
+
+  #[test]
+  fn should_parse_commands() {
+    let script = parse_script(&format!("{DRAWING}move 1 from 2 to 1; undo\n# comment\nswap 1 3\n\ndef shuffle {{\n  reverse 2\n  if top 1 == [P] then call shuffle\n}}\nundo 2")).unwrap();
+
+    let lines: Vec<usize> = script.statements().iter().map(|statement| statement.line).collect();
+    assert_eq!(vec![6, 6, 8, 10, 14], lines);
+
+    assert_eq!(Command::Def {
+      name: "shuffle".to_string(),
+      body: vec![
+        Command::Reverse(2),
+        Command::If { stack: 1, label: "P".to_string(), then: Box::new(Command::Call("shuffle".to_string())) },
+      ],
+    }, script.statements()[3].command);
+    assert_eq!(Command::Undo(2), script.statements()[4].command);
+  }
+
+  #[test]
+  fn should_run_commands() {
+    assert_eq!(Ok("MCD".to_string()), run("move 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2"));
+    assert_eq!(Ok("PDZ".to_string()), run("swap 1 3\nreverse 3"));
+    assert_eq!(Ok("NDP".to_string()), run("move 1 from 1 to 2\nreverse 2\nundo 2"));
+    assert_eq!(Ok("NDP".to_string()), run("move 1 from 1 to 2\nif top 1 == Z then move 1 from 2 to 1"));
+    assert_eq!(Ok("MCD".to_string()), run("def step { move 1 from 2 to 1; if top 1 == D then move 3 from 1 to 3 }\ncall step\nmove 2 from 2 to 1; move 1 from 1 to 2"));
+    assert_eq!(Ok("CMZ".to_string()), parse_script(&format!("{DRAWING}move 2 from 1 to 3\nundo\nmove 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2"))
+      .unwrap().run(&CrateMover9000).map(|stacks| stacks.tops()));
+  }
+
+  #[test]
+  fn should_report_script_errors() {
+    let error = parse_script(&format!("{DRAWING}swap 1 2\nmove 1 frm 2 to 1")).unwrap_err();
+    assert_eq!((7, 8, "expected `move N from A to B`"), (error.line, error.column, error.message.as_str()));
+
+    let error = parse_script(&format!("{DRAWING}def x {{\n  undo\n}}")).unwrap_err();
+    assert_eq!((7, 7, "`undo` only works at the top level"), (error.line, error.column, error.message.as_str()));
+
+    let error = parse_script(&format!("{DRAWING}jump 1")).unwrap_err();
+    assert_eq!((6, 1, "expected a command"), (error.line, error.column, error.message.as_str()));
+
+    assert_eq!(Err(ScriptError { line: 7, error: RunError::NothingToUndo { wanted: 2, available: 1 } }), run("swap 1 2\nundo 2"));
+    assert_eq!(Err(ScriptError { line: 6, error: RunError::UnknownMacro("x".to_string()) }), run("call x"));
+    assert_eq!(Err(ScriptError { line: 7, error: RunError::TooDeep("x".to_string()) }), run("def x { call x }\ncall x"));
+  }
+}
@@ -0,0 +1,180 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+
+use crate::{Crane, Move, Stacks};
+
+// What the stacks should look like once the moves are done.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+  // Top crate labels as printed by `Stacks::tops`.
+  Tops(String),
+  Layout(Stacks),
+}
+
+impl Target {
+  fn reached(&self, stacks: &Stacks) -> bool {
+    match self {
+      Target::Tops(tops) => stacks.tops() == *tops,
+      Target::Layout(layout) => stacks == layout,
+    }
+  }
+
+  // A move changes two stacks at most, so this never overestimates the
+  // moves left.
+  fn estimate(&self, stacks: &Stacks) -> usize {
+    match self {
+      Target::Tops(_) => usize::from(!self.reached(stacks)),
+      Target::Layout(layout) => {
+        let differing = (1..=stacks.len()).filter(|&k| stacks.stack(k) != layout.stack(k)).count();
+        differing.div_ceil(2)
+      }
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+  Unreachable,
+  TooManyStates(usize),
+}
+
+impl fmt::Display for SolveError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SolveError::Unreachable => write!(f, "no sequence of moves reaches the target"),
+      SolveError::TooManyStates(limit) => write!(f, "gave up after visiting {limit} states"),
+    }
+  }
+}
+
+// A* search for the fewest moves taking `start` to `target` with `crane`,
+// visiting at most `max_states` distinct stack layouts.
+pub fn solve(start: &Stacks, target: &Target, crane: &dyn Crane, max_states: usize) -> Result<Vec<Move>, SolveError> {
+  if let Target::Layout(layout) = target {
+    if !same_crates(start, layout) {
+      return Err(SolveError::Unreachable);
+    }
+  }
+
+  // every state reached, with the move leading to it from its parent
+  let mut states: Vec<(Stacks, Option<(usize, Move)>)> = vec![(start.clone(), None)];
+  let mut best: HashMap<Stacks, usize> = HashMap::from([(start.clone(), 0)]);
+  let mut open = BinaryHeap::from([Reverse((target.estimate(start), 0, 0))]);
+
+  while let Some(Reverse((_, depth, idx))) = open.pop() {
+    let stacks = states[idx].0.clone();
+    if best[&stacks] < depth {
+      continue;
+    }
+    if target.reached(&stacks) {
+      return Ok(path(&states, idx));
+    }
+
+    for mv in moves(&stacks) {
+      let mut next = stacks.clone();
+      next.apply(&mv, crane).expect("moves are built from the stacks");
+
+      if best.get(&next).is_some_and(|&known| known <= depth + 1) {
+        continue;
+      }
+      if best.len() >= max_states && !best.contains_key(&next) {
+        return Err(SolveError::TooManyStates(max_states));
+      }
+
+      best.insert(next.clone(), depth + 1);
+      open.push(Reverse((depth + 1 + target.estimate(&next), depth + 1, states.len())));
+      states.push((next, Some((idx, mv))));
+    }
+  }
+
+  Err(SolveError::Unreachable)
+}
+
+// Every move the stacks allow, whatever its length.
+fn moves(stacks: &Stacks) -> Vec<Move> {
+  let mut moves = vec![];
+
+  for src in 1..=stacks.len() {
+    let height = stacks.stack(src).map_or(0, <[_]>::len);
+
+    for dst in (1..=stacks.len()).filter(|&dst| dst != src) {
+      moves.extend((1..=height).map(|len| Move { len, src, dst }));
+    }
+  }
+
+  moves
+}
+
+fn path(states: &[(Stacks, Option<(usize, Move)>)], mut idx: usize) -> Vec<Move> {
+  let mut moves = vec![];
+
+  while let Some((parent, mv)) = states[idx].1 {
+    moves.push(mv);
+    idx = parent;
+  }
+
+  moves.reverse();
+  moves
+}
+
+fn same_crates(a: &Stacks, b: &Stacks) -> bool {
+  let labels = |stacks: &Stacks| {
+    let mut labels: Vec<String> = (1..=stacks.len())
+      .flat_map(|k| stacks.stack(k).unwrap_or_default())
+      .map(|item| item.label().to_string())
+      .collect();
+    labels.sort();
+    labels
+  };
+
+  a.len() == b.len() && labels(a) == labels(b)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{CrateMover9000, CrateMover9001};
+
+  const START: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ";
+
+  fn replay(start: &Stacks, moves: &[Move], crane: &dyn Crane) -> Stacks {
+    let mut stacks = start.clone();
+    for mv in moves {
+      stacks.apply(mv, crane).unwrap();
+    }
+    stacks
+  }
+
+  #[test]
+  fn should_find_the_fewest_moves() {
+    let start: Stacks = START.parse().unwrap();
+
+    assert_eq!(Ok(vec![]), solve(&start, &Target::Tops("NDP".to_string()), &CrateMover9000, 1000));
+
+    // the puzzle's four moves reach CMZ, two are enough
+    let moves = solve(&start, &Target::Tops("CMZ".to_string()), &CrateMover9000, 100_000).unwrap();
+    assert_eq!("CMZ", replay(&start, &moves, &CrateMover9000).tops());
+    assert_eq!(2, moves.len());
+
+    // B has to leave and come back under A, which takes three lifts
+    let start: Stacks = "[B]    \n[A]    \n 1   2 ".parse().unwrap();
+    let layout: Stacks = "[A]    \n[B]    \n 1   2 ".parse().unwrap();
+    let moves = solve(&start, &Target::Layout(layout.clone()), &CrateMover9001, 1000).unwrap();
+    assert_eq!(3, moves.len());
+    assert_eq!(layout, replay(&start, &moves, &CrateMover9001));
+
+    // the CrateMover 9000 reverses both crates in one move
+    assert_eq!(Ok(vec![Move { len: 2, src: 1, dst: 2 }]), solve(&start, &Target::Tops("A".to_string()), &CrateMover9000, 1000));
+  }
+
+  #[test]
+  fn should_report_unreachable_targets() {
+    let start: Stacks = START.parse().unwrap();
+
+    let layout: Stacks = "[Q]        \n 1   2   3 ".parse().unwrap();
+    assert_eq!(Err(SolveError::Unreachable), solve(&start, &Target::Layout(layout), &CrateMover9001, 1000));
+    assert_eq!(Err(SolveError::Unreachable), solve(&"[A]    \n 1   2 ".parse().unwrap(), &Target::Tops("B".to_string()), &CrateMover9001, 1000));
+    assert_eq!(Err(SolveError::TooManyStates(10)), solve(&start, &Target::Tops("QQQ".to_string()), &CrateMover9001, 10));
+  }
+}
@@ -0,0 +1,177 @@
+use std::fmt;
+use std::str::FromStr;
+
+use common::ParseError;
+
+use crate::{Crane, Move};
+
+// A crate, known by the label drawn between its brackets.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Crate(String);
+
+impl Crate {
+  pub fn new(label: &str) -> Crate {
+    Crate(label.to_string())
+  }
+
+  pub fn label(&self) -> &str {
+    &self.0
+  }
+}
+
+impl fmt::Display for Crate {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "[{}]", self.0)
+  }
+}
+
+// Why a move cannot be done on the current stacks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveError {
+  MissingStack { stack: usize, stacks: usize },
+  NotEnoughCrates { stack: usize, wanted: usize, available: usize },
+}
+
+impl fmt::Display for MoveError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      MoveError::MissingStack { stack, stacks } => {
+        write!(f, "there is no stack {stack}, the drawing numbers {stacks}")
+      }
+      MoveError::NotEnoughCrates { stack, wanted, available } => {
+        write!(f, "cannot move {wanted} crates from stack {stack}, it holds {available}")
+      }
+    }
+  }
+}
+
+// Stacks numbered from 1 as in the drawing footer, each one bottom first.
+// Prints back to the drawing format, which `from_str` reads again, so a
+// state can be saved and reloaded.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Stacks(Vec<Vec<Crate>>);
+
+impl Stacks {
+  pub fn new(count: usize) -> Stacks {
+    Stacks(vec![vec![]; count])
+  }
+
+  pub fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  // The crates of stack `number`, bottom first.
+  pub fn stack(&self, number: usize) -> Option<&[Crate]> {
+    number.checked_sub(1).and_then(|i| self.0.get(i)).map(Vec::as_slice)
+  }
+
+  pub(crate) fn push(&mut self, number: usize, item: Crate) {
+    self.0[number - 1].push(item);
+  }
+
+  // Top crate labels, empty stacks are skipped.
+  pub fn tops(&self) -> String {
+    self.0.iter().filter_map(|stack| stack.last()).map(Crate::label).collect()
+  }
+
+  // The top crate label of stack `number`, `None` when it is empty.
+  pub fn top(&self, number: usize) -> Result<Option<&str>, MoveError> {
+    let stack = self.stack(number).ok_or(MoveError::MissingStack { stack: number, stacks: self.len() })?;
+    Ok(stack.last().map(Crate::label))
+  }
+
+  pub fn swap(&mut self, a: usize, b: usize) -> Result<(), MoveError> {
+    self.top(a)?;
+    self.top(b)?;
+    self.0.swap(a - 1, b - 1);
+    Ok(())
+  }
+
+  // Turns stack `number` upside down.
+  pub fn reverse(&mut self, number: usize) -> Result<(), MoveError> {
+    self.top(number)?;
+    self.0[number - 1].reverse();
+    Ok(())
+  }
+
+  pub fn check(&self, mv: &Move) -> Result<(), MoveError> {
+    for stack in [mv.src, mv.dst] {
+      if self.stack(stack).is_none() {
+        return Err(MoveError::MissingStack { stack, stacks: self.len() });
+      }
+    }
+
+    let available = self.0[mv.src - 1].len();
+    if available < mv.len {
+      return Err(MoveError::NotEnoughCrates { stack: mv.src, wanted: mv.len, available });
+    }
+
+    Ok(())
+  }
+
+  // Returns the moved crates as they now sit on the destination, bottom first.
+  pub fn apply(&mut self, mv: &Move, crane: &dyn Crane) -> Result<Vec<Crate>, MoveError> {
+    self.check(mv)?;
+
+    for lift in crane.lifts(mv.len) {
+      let src = &mut self.0[mv.src - 1];
+      let mut lifted = src.split_off(src.len() - lift);
+      self.0[mv.dst - 1].append(&mut lifted);
+    }
+
+    let dst = &self.0[mv.dst - 1];
+    Ok(dst[dst.len() - mv.len..].to_vec())
+  }
+}
+
+// The puzzle's drawing format, numbered footer included.
+impl fmt::Display for Stacks {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", crate::drawing::render(self))
+  }
+}
+
+impl FromStr for Stacks {
+  type Err = ParseError;
+
+  fn from_str(drawing: &str) -> Result<Stacks, ParseError> {
+    crate::drawing::parse_drawing(drawing)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{CrateMover9000, CrateMover9001};
+
+  #[test]
+  fn should_reject_impossible_moves() {
+    let mut stacks: Stacks = "[A]    \n 1   2 ".parse().unwrap();
+
+    let mv = Move { len: 1, src: 2, dst: 1 };
+    assert_eq!(Err(MoveError::NotEnoughCrates { stack: 2, wanted: 1, available: 0 }), stacks.apply(&mv, &CrateMover9000));
+
+    let mv = Move { len: 1, src: 1, dst: 3 };
+    assert_eq!(Err(MoveError::MissingStack { stack: 3, stacks: 2 }), stacks.apply(&mv, &CrateMover9001));
+
+    let mv = Move { len: 1, src: 1, dst: 2 };
+    assert_eq!(Ok(vec![Crate::new("A")]), stacks.apply(&mv, &CrateMover9000));
+    assert_eq!("A", stacks.tops());
+  }
+
+  #[test]
+  fn should_reload_a_saved_state() {
+    let mut stacks = Stacks::new(3);
+    stacks.push(1, Crate::new("Z"));
+    stacks.push(1, Crate::new("N"));
+    stacks.push(3, Crate::new("P"));
+
+    let saved = stacks.to_string();
+    assert_eq!("[N]        \n[Z]     [P]\n 1   2   3 ", saved);
+    assert_eq!(stacks, saved.parse().unwrap());
+  }
+}
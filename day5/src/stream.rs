@@ -0,0 +1,136 @@
+use std::fmt;
+use std::io::{self, BufRead};
+
+use common::ParseError;
+
+use crate::{Crane, Stacks};
+
+// How far a streamed run got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+  pub moves: usize,
+  pub line: usize,
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+  Io(io::Error),
+  Parse(ParseError),
+}
+
+impl From<io::Error> for StreamError {
+  fn from(e: io::Error) -> StreamError {
+    StreamError::Io(e)
+  }
+}
+
+impl From<ParseError> for StreamError {
+  fn from(e: ParseError) -> StreamError {
+    StreamError::Parse(e)
+  }
+}
+
+impl fmt::Display for StreamError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      StreamError::Io(e) => write!(f, "{e}"),
+      StreamError::Parse(e) => write!(f, "{e}"),
+    }
+  }
+}
+
+// Reads the drawing, then applies each move as soon as its line is read, so
+// only the stacks are kept in memory. `on_progress` is called every `every`
+// moves, never when `every` is 0.
+pub fn run_stream<R: BufRead>(
+  mut reader: R,
+  crane: &dyn Crane,
+  every: usize,
+  on_progress: &mut dyn FnMut(Progress),
+) -> Result<Stacks, StreamError> {
+  let mut buffer = String::new();
+  let mut line_no = 0;
+
+  let mut drawing = String::new();
+  loop {
+    buffer.clear();
+    if reader.read_line(&mut buffer)? == 0 {
+      let message = "expected a blank line between the drawing and the moves";
+      return Err(ParseError::new(line_no.max(1), 1, "", message).into());
+    }
+    line_no += 1;
+
+    let line = buffer.trim_end_matches(['\n', '\r']);
+    if line.is_empty() {
+      break;
+    }
+    if !drawing.is_empty() {
+      drawing.push('\n');
+    }
+    drawing.push_str(line);
+  }
+
+  let mut stacks = crate::drawing::parse_drawing(&drawing)?;
+  let mut moves = 0;
+
+  loop {
+    buffer.clear();
+    if reader.read_line(&mut buffer)? == 0 {
+      return Ok(stacks);
+    }
+    line_no += 1;
+
+    let line = buffer.trim_end_matches(['\n', '\r']);
+    if line.trim().is_empty() {
+      continue;
+    }
+
+    let mv = crate::parse_move(line).map_err(|e| ParseError { line: line_no, ..e })?;
+    stacks.apply(&mv, crane).map_err(|e| ParseError::line(line_no, line, e.to_string()))?;
+
+    moves += 1;
+    if every > 0 && moves % every == 0 {
+      on_progress(Progress { moves, line: line_no });
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{CrateMover9000, CrateMover9001};
+
+  const INPUT: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
+
+  #[test]
+  fn should_stream_moves() {
+    let mut reports = vec![];
+    let stacks = run_stream(INPUT.as_bytes(), &CrateMover9000, 2, &mut |progress| reports.push(progress)).unwrap();
+
+    assert_eq!("CMZ", stacks.tops());
+    assert_eq!(vec![Progress { moves: 2, line: 7 }, Progress { moves: 4, line: 9 }], reports);
+
+    let crlf = INPUT.replace('\n', "\r\n");
+    let stacks = run_stream(crlf.as_bytes(), &CrateMover9001, 0, &mut |_| unreachable!()).unwrap();
+    assert_eq!("MCD", stacks.tops());
+    assert_eq!("MCD", crate::parse(&crlf).unwrap().run(&CrateMover9001));
+  }
+
+  #[test]
+  fn should_report_the_failing_line() {
+    let input = INPUT.replace("move 2 from 2 to 1", "move 9 from 2 to 1");
+    let Err(StreamError::Parse(error)) = run_stream(input.as_bytes(), &CrateMover9000, 0, &mut |_| ()) else {
+      panic!("expected a parse error");
+    };
+    assert_eq!((8, "cannot move 9 crates from stack 2, it holds 2"), (error.line, error.message.as_str()));
+  }
+}
@@ -0,0 +1,33 @@
+use std::fmt;
+
+use crate::{Crate, Move, Stacks};
+
+// One executed move: the crates it put on the destination, bottom first,
+// and the stacks right after it.
+#[derive(Debug, Clone)]
+pub struct Step {
+  pub number: usize,
+  pub mv: Move,
+  pub moved: Vec<Crate>,
+  pub stacks: Stacks,
+}
+
+impl fmt::Display for Step {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let moved: Vec<String> = self.moved.iter().map(Crate::to_string).collect();
+
+    writeln!(f, "step {}: {} {}", self.number, self.mv, moved.join(" "))?;
+    write!(f, "{}", self.stacks)
+  }
+}
+
+// The starting drawing followed by every step, as plain text for diffing.
+pub fn render_trace(initial: &Stacks, steps: &[Step]) -> String {
+  let mut trace = format!("initial\n{initial}\n");
+
+  for step in steps {
+    trace.push_str(&format!("\n{step}\n"));
+  }
+
+  trace
+}
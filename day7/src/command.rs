@@ -0,0 +1,215 @@
+use crate::Kind;
+
+// What a `$` line does to the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command<'l> {
+    Cd(&'l str),
+    // Lists the working directory, or the directory at the path.
+    Ls(Option<&'l str>),
+}
+
+// Tells which lines of a transcript shape the tree. Commands that no
+// recognizer knows are skipped along with their output, with a warning.
+pub trait Recognizer {
+    // `command` is the line after its `$`, trimmed.
+    fn recognize<'l>(&self, command: &'l str) -> Option<Command<'l>>;
+}
+
+// `cd PATH` and `ls [-FLAGS] [PATH]` as a POSIX shell runs them. `cd` alone
+// or `cd -` depend on where the session started, so they are not followed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Posix;
+
+impl Recognizer for Posix {
+    fn recognize<'l>(&self, command: &'l str) -> Option<Command<'l>> {
+        let (name, args) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+        let args = args.trim();
+
+        match name {
+            "cd" if !args.is_empty() && args != "-" => Some(Command::Cd(args)),
+            "ls" => {
+                let mut paths = args.split_whitespace().filter(|arg| !arg.starts_with('-'));
+                match (paths.next(), paths.next()) {
+                    (path, None) => Some(Command::Ls(path)),
+                    // each directory would come under its own header
+                    (_, Some(_)) => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+// An entry of `ls` output: `dir NAME` or `SIZE NAME`, or with `-l` the
+// permissions, links, owner, group, size, date and name columns, where a
+// symlink's name is followed by `-> TARGET`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Listed<'l> {
+    pub kind: Kind,
+    pub name: &'l str,
+    pub size: u64,
+    pub perms: Option<&'l str>,
+    pub owner: Option<&'l str>,
+    pub target: Option<&'l str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Listing<'l> {
+    Entry(Listed<'l>),
+    // `total 48`, `.` and `..`.
+    Nothing,
+    // Anything else, with the fragment at fault and why it is skipped.
+    Unrecognized(&'l str, &'static str),
+}
+
+// One line of `ls` output. Sizes that do not fit in 64 bits are an error
+// rather than something to skip, with the fragment at fault and why.
+pub fn parse_listing(line: &str) -> Result<Listing<'_>, (&str, &'static str)> {
+    let line = line.trim();
+    let (first_word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim_start();
+
+    if first_word == "total" {
+        return Ok(Listing::Nothing);
+    }
+    if is_perms(first_word) {
+        return parse_long(line);
+    }
+    if rest.is_empty() {
+        return Ok(Listing::Unrecognized(line, "expected `dir NAME` or `SIZE NAME`"));
+    }
+
+    let (kind, size) = if first_word == "dir" {
+        (Kind::Dir, 0)
+    } else if first_word.bytes().all(|b| b.is_ascii_digit()) {
+        (Kind::File, parse_size(first_word)?)
+    } else if first_word.starts_with("ls:") {
+        return Ok(Listing::Unrecognized(line, "`ls` reported an error"));
+    } else {
+        return Ok(Listing::Unrecognized(first_word, "invalid file size"));
+    };
+
+    Ok(entry(kind, rest, size, None, None))
+}
+
+// `-rw-r--r-- 1 owner group 1234 Jan  2 12:00 name`, without the owner or
+// the group with `-g` or `-o`, and with `2023-01-02 12:00` dates too.
+fn parse_long(line: &str) -> Result<Listing<'_>, (&str, &'static str)> {
+    let mut words = vec![];
+    let mut rest = line;
+    while let Some((word, after)) = rest.split_once(char::is_whitespace) {
+        words.push(word);
+        rest = after.trim_start();
+
+        // the name starts after the date, which may not be there yet
+        let date = (3..words.len()).find(|&i| {
+            let size = words[i - 1].bytes().all(|b| b.is_ascii_digit());
+            let month = is_month(words[i]) && words.len() == i + 3;
+            let day = is_iso_date(words[i]) && words.len() == i + 2;
+            size && (month || day)
+        });
+
+        if let Some(date) = date {
+            let perms = words[0];
+            let kind = match perms.as_bytes()[0] {
+                b'd' => Kind::Dir,
+                b'l' => Kind::Link,
+                b'-' => Kind::File,
+                _ => return Ok(Listing::Unrecognized(perms, "not a directory, file or symlink")),
+            };
+            let size = if kind == Kind::File { parse_size(words[date - 1])? } else { 0 };
+            let owner = if date > 3 { Some(words[2]) } else { None };
+
+            return Ok(entry(kind, rest, size, Some(perms), owner));
+        }
+    }
+
+    Ok(Listing::Unrecognized(line, "expected `ls -l` columns"))
+}
+
+fn entry<'l>(kind: Kind, name: &'l str, size: u64, perms: Option<&'l str>, owner: Option<&'l str>) -> Listing<'l> {
+    let (name, target) = match (kind, name.split_once(" -> ")) {
+        (Kind::Link, Some((name, target))) => (name, Some(target)),
+        _ => (name, None),
+    };
+
+    if name == "." || name == ".." {
+        return Listing::Nothing;
+    }
+
+    Listing::Entry(Listed {
+        kind,
+        name,
+        size,
+        perms,
+        owner,
+        target,
+    })
+}
+
+fn parse_size(size: &str) -> Result<u64, (&str, &'static str)> {
+    size.parse().map_err(|_| (size, "file size does not fit in 64 bits"))
+}
+
+// `drwxr-xr-x`, maybe followed by `+`, `@` or `.` for extended attributes.
+fn is_perms(word: &str) -> bool {
+    let bytes = word.as_bytes();
+    bytes.len() >= 10
+        && b"-dlcbps".contains(&bytes[0])
+        && bytes[1..10].iter().all(|b| b"rwxsStT-".contains(b))
+        && matches!(&word[10..], "" | "+" | "@" | ".")
+}
+
+fn is_month(word: &str) -> bool {
+    ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"].contains(&word)
+}
+
+fn is_iso_date(word: &str) -> bool {
+    let bytes = word.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognize_posix_commands() {
+        assert_eq!(Some(Command::Cd("my dir")), Posix.recognize("cd my dir"));
+        assert_eq!(Some(Command::Ls(None)), Posix.recognize("ls -la"));
+        assert_eq!(Some(Command::Ls(Some("a/b"))), Posix.recognize("ls -l a/b"));
+        assert_eq!(None, Posix.recognize("ls a b"));
+        assert_eq!(None, Posix.recognize("cd"));
+        assert_eq!(None, Posix.recognize("pwd"));
+    }
+
+    #[test]
+    fn parse_listings() {
+        let listed = |line| match parse_listing(line) {
+            Ok(Listing::Entry(listed)) => (listed.kind, listed.name, listed.size, listed.owner, listed.target),
+            other => panic!("{line}: {other:?}"),
+        };
+
+        assert_eq!((Kind::Dir, "a", 0, None, None), listed("dir a"));
+        assert_eq!((Kind::File, "b.txt", 14848514, None, None), listed("14848514 b.txt"));
+        assert_eq!(
+            (Kind::File, "my notes.txt", 1234, Some("alice"), None),
+            listed("-rw-r--r--  1 alice staff  1234 Jan  2 12:00 my notes.txt")
+        );
+        assert_eq!((Kind::Dir, "src", 0, Some("bob"), None), listed("drwxr-xr-x. 4 bob bob 4096 2023-01-02 12:00 src"));
+        assert_eq!(
+            (Kind::Link, "latest", 0, Some("root"), Some("/var/log/app.log")),
+            listed("lrwxrwxrwx 1 root 16 Mar  3  2021 latest -> /var/log/app.log")
+        );
+
+        assert_eq!(Ok(Listing::Nothing), parse_listing("total 48"));
+        assert_eq!(Ok(Listing::Nothing), parse_listing("drwxr-xr-x 2 bob bob 4096 Jan  2 12:00 .."));
+        assert!(matches!(parse_listing("ls: cannot access 'x': No such file or directory"), Ok(Listing::Unrecognized(..))));
+        assert!(matches!(parse_listing("crw-rw-rw- 1 root root 1, 3 Jan 2 12:00 null"), Ok(Listing::Unrecognized("crw-rw-rw-", _))));
+        assert_eq!(Err(("18446744073709551616", "file size does not fit in 64 bits")), parse_listing("18446744073709551616 a"));
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use common::json;
+
+use crate::report::csv_field;
+use crate::{Entry, FileSystem, Format, Kind, View};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Added,
+    Removed,
+    Resized,
+}
+
+impl Status {
+    fn name(self) -> &'static str {
+        match self {
+            Status::Added => "added",
+            Status::Removed => "removed",
+            Status::Resized => "resized",
+        }
+    }
+
+    fn sign(self) -> char {
+        match self {
+            Status::Added => '+',
+            Status::Removed => '-',
+            Status::Resized => '~',
+        }
+    }
+}
+
+// A path that is new, gone, or of another size in the later transcript. A
+// directory's size is everything under it, so a file's change shows again
+// in every directory above it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub path: String,
+    pub kind: Kind,
+    pub status: Status,
+    pub before: Option<u64>,
+    pub after: Option<u64>,
+}
+
+impl Change {
+    pub fn delta(&self) -> i128 {
+        self.after.unwrap_or(0) as i128 - self.before.unwrap_or(0) as i128
+    }
+}
+
+// What changed from `before` to `after`, by path. A path that turned from a
+// file into a directory or back is removed and added again.
+pub fn diff(before: &FileSystem, after: &FileSystem) -> Vec<Change> {
+    let by_path = |fs: &FileSystem| -> BTreeMap<String, Entry> {
+        fs.report(View::List, None)
+            .into_iter()
+            .map(|entry| (entry.path.clone(), entry))
+            .collect()
+    };
+    let (before, after) = (by_path(before), by_path(after));
+
+    let mut paths: Vec<&String> = before.keys().chain(after.keys()).collect();
+    paths.sort();
+    paths.dedup();
+
+    let mut changes = vec![];
+    for path in paths {
+        let change = |entry: &Entry, status, before, after| Change {
+            path: path.clone(),
+            kind: entry.kind,
+            status,
+            before,
+            after,
+        };
+
+        match (before.get(path), after.get(path)) {
+            (Some(old), Some(new)) if old.kind == new.kind => {
+                if old.size != new.size {
+                    changes.push(change(new, Status::Resized, Some(old.size), Some(new.size)));
+                }
+            }
+            (old, new) => {
+                if let Some(old) = old {
+                    changes.push(change(old, Status::Removed, Some(old.size), None));
+                }
+                if let Some(new) = new {
+                    changes.push(change(new, Status::Added, None, Some(new.size)));
+                }
+            }
+        }
+    }
+
+    changes
+}
+
+pub fn render_diff(changes: &[Change], format: Format) -> String {
+    let mut output = String::new();
+    let size = |size: Option<u64>| size.map_or(String::new(), |size| size.to_string());
+
+    match format {
+        Format::Text => {
+            for change in changes {
+                let sizes = match (change.before, change.after) {
+                    (Some(before), Some(after)) => format!("{before} -> {after}"),
+                    (before, after) => size(before.or(after)),
+                };
+                let _ = writeln!(
+                    output,
+                    "{} {:<4}  {}  {sizes} ({:+})",
+                    change.status.sign(),
+                    change.kind.name(),
+                    change.path,
+                    change.delta()
+                );
+            }
+
+            let count = |status| changes.iter().filter(|change| change.status == status).count();
+            let _ = writeln!(
+                output,
+                "{} added, {} removed, {} resized",
+                count(Status::Added),
+                count(Status::Removed),
+                count(Status::Resized)
+            );
+        }
+        Format::Json => {
+            let objects: Vec<String> = changes
+                .iter()
+                .map(|change| {
+                    let size = |size: Option<u64>| size.map_or("null".to_string(), |size| size.to_string());
+                    format!(
+                        "{{\"path\":{},\"type\":{},\"change\":{},\"before\":{},\"after\":{},\"delta\":{}}}",
+                        json::string(&change.path),
+                        json::string(change.kind.name()),
+                        json::string(change.status.name()),
+                        size(change.before),
+                        size(change.after),
+                        change.delta(),
+                    )
+                })
+                .collect();
+            let _ = writeln!(output, "[{}]", objects.join(","));
+        }
+        Format::Csv => {
+            output.push_str("path,type,change,before,after,delta\n");
+            for change in changes {
+                let _ = writeln!(
+                    output,
+                    "{},{},{},{},{},{}",
+                    csv_field(&change.path),
+                    change.kind.name(),
+                    change.status.name(),
+                    size(change.before),
+                    size(change.after),
+                    change.delta()
+                );
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEFORE: &str = "$ cd /
+$ ls
+dir a
+100 b.txt
+dir c
+$ cd a
+$ ls
+20 x
+30 y
+$ cd /c
+$ ls
+5 z";
+
+    const AFTER: &str = "$ cd /
+$ ls
+dir a
+100 b.txt
+5 c
+dir d
+$ cd a
+$ ls
+20 x
+45 y
+$ cd /d
+$ ls
+7 w";
+
+    #[test]
+    fn diff_two_sessions() {
+        let (mut before, mut after) = (FileSystem::new(), FileSystem::new());
+        before.parse_cmds(BEFORE).unwrap();
+        after.parse_cmds(AFTER).unwrap();
+
+        let changes = diff(&before, &after);
+        assert_eq!(
+            "~ dir   /  155 -> 177 (+22)
+~ dir   /a  50 -> 65 (+15)
+~ file  /a/y  30 -> 45 (+15)
+- dir   /c  5 (-5)
++ file  /c  5 (+5)
+- file  /c/z  5 (-5)
++ dir   /d  7 (+7)
++ file  /d/w  7 (+7)
+3 added, 2 removed, 3 resized
+",
+            render_diff(&changes, Format::Text)
+        );
+
+        assert!(render_diff(&changes, Format::Json).starts_with(
+            "[{\"path\":\"/\",\"type\":\"dir\",\"change\":\"resized\",\"before\":155,\"after\":177,\"delta\":22},"
+        ));
+        assert!(diff(&after, &after).is_empty());
+    }
+}
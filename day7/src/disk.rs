@@ -0,0 +1,221 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::symlink;
+use std::path::Path;
+
+use crate::{FileSystem, Kind};
+
+// Elsewhere links need to know whether they point at a directory, which
+// the transcript does not say.
+#[cfg(not(unix))]
+fn symlink(_target: &str, path: &Path) -> io::Result<()> {
+    let message = format!("cannot create symlink {} on this platform", path.display());
+    Err(io::Error::new(io::ErrorKind::Unsupported, message))
+}
+
+impl FileSystem {
+    // Recreates the tree under `dir`, which stands for the root: directories
+    // as directories, files as sparse files of their recorded size and
+    // symlinks pointing where they did, even if that is outside `dir`. `dir`
+    // has to be new or empty, so nothing already there is overwritten.
+    pub fn materialize(&self, dir: &Path) -> io::Result<()> {
+        if let Err(e) = fs::create_dir(dir) {
+            if e.kind() != io::ErrorKind::AlreadyExists || fs::read_dir(dir)?.next().is_some() {
+                return Err(e);
+            }
+        }
+
+        let Some(root_dir) = self.root() else {
+            return Ok(());
+        };
+        let mut stack = vec![(root_dir, dir.to_path_buf())];
+
+        while let Some((id, path)) = stack.pop() {
+            for &child in self.children(id) {
+                let name = self.name(child);
+                if name == "." || name == ".." || name.contains('/') {
+                    let message = format!("`{name}` in {} cannot be a file name", self.path(id));
+                    return Err(io::Error::new(io::ErrorKind::InvalidInput, message));
+                }
+
+                let child_path = path.join(name);
+                match self.kind(child) {
+                    Kind::Dir => {
+                        fs::create_dir(&child_path)?;
+                        stack.push((child, child_path));
+                    }
+                    // growing an empty file leaves a hole instead of writing zeroes
+                    Kind::File => {
+                        let file = OpenOptions::new().write(true).create_new(true).open(&child_path)?;
+                        file.set_len(self.size(child))?
+                    }
+                    Kind::Link => symlink(self.target(child).unwrap_or_default(), &child_path)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Writes the `$ cd` / `$ ls` transcript of walking `dir`, which becomes `/`.
+// Entries come in name order, symlinks as `ls -l` lines with a made-up date,
+// and anything but directories, regular files and symlinks is left out. Names
+// that a listing line cannot hold, such as ones with a newline, are an error.
+pub fn write_transcript(dir: &Path, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "$ cd /")?;
+    walk(dir, out)
+}
+
+enum Walked {
+    Dir,
+    File(u64),
+    Link(String),
+}
+
+fn walk(dir: &Path, out: &mut dyn Write) -> io::Result<()> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let mut entries = vec![];
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+
+        let name = entry
+            .file_name()
+            .into_string()
+            .map_err(|name| invalid(format!("{} is not valid UTF-8", dir.join(name).display())))?;
+        let walked = if file_type.is_dir() {
+            Walked::Dir
+        } else if file_type.is_file() {
+            Walked::File(entry.metadata()?.len())
+        } else if file_type.is_symlink() {
+            let target = fs::read_link(entry.path())?;
+            let target = target
+                .to_str()
+                .ok_or_else(|| invalid(format!("{} points at a path that is not valid UTF-8", entry.path().display())))?;
+            Walked::Link(target.to_string())
+        } else {
+            continue;
+        };
+
+        // listing lines are trimmed, and a link's name ends at its arrow
+        let arrow = matches!(walked, Walked::Link(_)) && name.contains(" -> ");
+        if name.trim() != name || name.contains(char::is_control) || arrow {
+            return Err(invalid(format!("`{name}` in {} is not a name an `ls` line can hold", dir.display())));
+        }
+        if let Walked::Link(target) = &walked {
+            if target.contains(char::is_control) {
+                let message = format!("{} points at `{target}`, which an `ls` line cannot hold", entry.path().display());
+                return Err(invalid(message));
+            }
+        }
+
+        entries.push((name, walked));
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    writeln!(out, "$ ls")?;
+    for (name, walked) in &entries {
+        match walked {
+            Walked::Dir => writeln!(out, "dir {name}")?,
+            Walked::File(size) => writeln!(out, "{size} {name}")?,
+            Walked::Link(target) => writeln!(out, "lrwxrwxrwx 1 {} Jan  1  1970 {name} -> {target}", target.len())?,
+        }
+    }
+
+    for (name, _) in entries.iter().filter(|(_, walked)| matches!(walked, Walked::Dir)) {
+        // `cd -` goes back rather than into `-`
+        match name.as_str() {
+            "-" => writeln!(out, "$ cd ./-")?,
+            _ => writeln!(out, "$ cd {name}")?,
+        }
+        walk(&dir.join(name), out)?;
+        writeln!(out, "$ cd ..")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::process;
+
+    use super::*;
+    use crate::View;
+
+    #[test]
+    fn round_trip_through_disk() {
+        let fs = crate::example();
+
+        let dir = env::temp_dir().join(format!("day7-round-trip-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs.materialize(&dir).unwrap();
+        assert_eq!(29116, fs::metadata(dir.join("a/f")).unwrap().len());
+
+        let mut walked = vec![];
+        let result = write_transcript(&dir, &mut walked);
+        fs::remove_dir_all(&dir).unwrap();
+        result.unwrap();
+
+        // the walk lists entries in name order, which the example does not
+        let walked = String::from_utf8(walked).unwrap();
+        assert!(walked.starts_with("$ cd /\n$ ls\ndir a\n14848514 b.txt\n8504156 c.dat\ndir d\n$ cd a\n"));
+
+        let mut again = FileSystem::new();
+        again.parse_cmds(&walked).unwrap();
+        let sorted = |fs: &FileSystem| {
+            let mut entries = fs.report(View::List, None);
+            entries.sort_by(|a, b| a.path.cmp(&b.path));
+            entries
+        };
+        assert_eq!(sorted(&fs), sorted(&again));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn round_trip_links_and_spaces() {
+        let transcript = "$ cd /
+$ ls
+lrwxrwxrwx 1 16 Jan  1  1970 latest -> /var/log/app.log
+dir my dir
+5 my notes.txt
+$ cd my dir
+$ ls
+7 x
+$ cd ..
+";
+        let mut fs = FileSystem::new();
+        fs.parse_cmds(transcript).unwrap();
+
+        let dir = env::temp_dir().join(format!("day7-round-trip-links-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs.materialize(&dir).unwrap();
+
+        let mut walked = vec![];
+        let result = write_transcript(&dir, &mut walked);
+        fs::remove_dir_all(&dir).unwrap();
+        result.unwrap();
+
+        assert_eq!(transcript, String::from_utf8(walked).unwrap());
+    }
+
+    #[test]
+    fn keep_what_is_already_there() {
+        let dir = env::temp_dir().join(format!("day7-existing-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("b.txt"), "precious data").unwrap();
+
+        let result = crate::example().materialize(&dir);
+        let kept = fs::read_to_string(dir.join("b.txt"));
+        let entries = fs::read_dir(&dir).map(|entries| entries.count());
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(io::ErrorKind::AlreadyExists, result.unwrap_err().kind());
+        assert_eq!("precious data", kept.unwrap());
+        assert_eq!(1, entries.unwrap());
+    }
+}
@@ -0,0 +1,898 @@
+use std::collections::HashMap;
+
+use common::{Answer, ParseError, Result, Solution};
+
+pub use command::{parse_listing, Command, Listed, Listing, Posix, Recognizer};
+pub use diff::{diff, render_diff, Change, Status};
+pub use disk::write_transcript;
+pub use planner::{CapacityError, Plan, Selection};
+pub use query::{Aggregate, Aggregated, NumberField, Query};
+pub use report::{render, Entry, Format, Kind, View};
+pub use shell::Shell;
+
+mod command;
+mod diff;
+mod disk;
+mod planner;
+mod query;
+mod report;
+mod shell;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NodeType {
+    Dir(u64),
+    File(u64),
+    // The target as `ls -l` printed it; links take no space of their own.
+    Symlink(String),
+}
+
+// Index of a node in its `FileSystem`, the root being the first one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+#[derive(Debug, Clone)]
+struct Node {
+    name: String,
+    node_type: NodeType,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+}
+
+#[derive(Debug, Default)]
+pub struct FileSystem {
+    nodes: Vec<Node>,
+    // each child by its parent and name, so listings stay linear
+    by_name: HashMap<(NodeId, String), NodeId>,
+    current_dir: Option<NodeId>,
+    warnings: Vec<ParseError>,
+}
+
+impl FileSystem {
+    pub fn new() -> FileSystem {
+        FileSystem {
+            nodes: vec![],
+            by_name: HashMap::new(),
+            current_dir: None,
+            warnings: vec![],
+        }
+    }
+
+    pub fn parse_cmds(&mut self, l: &str) -> Result<()> {
+        self.parse_with(l, &[&Posix])
+    }
+
+    // Follows the commands that `recognizers` know, the first one knowing a
+    // command deciding what it does. Everything else is skipped with a
+    // warning, and only what would leave the tree wrong is an error.
+    pub fn parse_with(&mut self, l: &str, recognizers: &[&dyn Recognizer]) -> Result<()> {
+        let mut line = l
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .filter(|(_, line)| !line.trim().is_empty())
+            .peekable();
+
+        while let Some((line_no, cmd_line)) = line.next() {
+            let mut output: Vec<(usize, &str)> = vec![];
+
+            while let Some((_, list_item)) = line.peek() {
+                if list_item.trim_start().starts_with('$') {
+                    break;
+                }
+
+                output.push(line.next().unwrap())
+            }
+
+            let Some(command) = cmd_line.trim_start().strip_prefix('$').map(str::trim) else {
+                self.warn(ParseError::line(line_no, cmd_line, "output without a command, skipped"));
+                continue;
+            };
+
+            match recognizers.iter().find_map(|recognizer| recognizer.recognize(command)) {
+                Some(Command::Cd(arg)) => {
+                    self.cd(line_no, cmd_line, arg)?;
+                    if let Some(&(line_no, item)) = output.first() {
+                        self.warn(ParseError::line(line_no, item, "output after `cd`, skipped"));
+                    }
+                }
+                Some(Command::Ls(path)) => {
+                    let current_dir = self.current_dir.ok_or_else(|| {
+                        ParseError::line(line_no, cmd_line, "`ls` outside of any directory")
+                    })?;
+
+                    match path.map(|path| self.resolve(current_dir, path, true)) {
+                        None => self.ls(current_dir, output)?,
+                        Some(Ok(dir)) => self.ls(dir, output)?,
+                        Some(Err((segment, message))) => {
+                            let message = format!("{message}, output skipped");
+                            self.warn(ParseError::at(line_no, cmd_line, segment, message));
+                        }
+                    }
+                }
+                None if command.is_empty() => self.warn(ParseError::line(line_no, cmd_line, "empty command, skipped")),
+                None => {
+                    let name = command.split_whitespace().next().unwrap_or(command);
+                    self.warn(ParseError::at(line_no, cmd_line, name, format!("`{name}` skipped")));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // What the last parse skipped, in transcript order.
+    pub fn warnings(&self) -> &[ParseError] {
+        &self.warnings
+    }
+
+    fn warn(&mut self, warning: ParseError) {
+        self.warnings.push(warning);
+    }
+
+    // `arg` is a path as `resolve` follows it. Every directory on the way
+    // must have been listed by an earlier `ls`.
+    fn cd(&mut self, line_no: usize, cmd_line: &str, arg: &str) -> Result<()> {
+        let absolute = arg.starts_with('/');
+
+        let root_dir = match self.root() {
+            Some(root_dir) => root_dir,
+            None => {
+                // the transcript starts in whichever directory it goes to first
+                let root_dir = self.add_root(if absolute { "/" } else { arg });
+                self.current_dir = Some(root_dir);
+
+                if !absolute {
+                    return Ok(());
+                }
+                root_dir
+            }
+        };
+
+        let dir = self
+            .resolve(self.current_dir.unwrap_or(root_dir), arg, true)
+            .map_err(|(segment, message)| ParseError::at(line_no, cmd_line, segment, message))?;
+
+        self.current_dir = Some(dir);
+        Ok(())
+    }
+
+    // Where `path` leads from `from`: a leading `/` starts from the root, `.`
+    // stays and `..` goes up, stopping at the root. Only the last segment may
+    // name a file, and not even that one with `dir_only`. On failure, the
+    // segment at fault and why.
+    pub(crate) fn resolve<'p>(
+        &self,
+        from: NodeId,
+        path: &'p str,
+        dir_only: bool,
+    ) -> std::result::Result<NodeId, (&'p str, String)> {
+        let mut id = if path.starts_with('/') { NodeId(0) } else { from };
+        let mut segments = path.split('/').filter(|segment| !segment.is_empty()).peekable();
+
+        while let Some(segment) = segments.next() {
+            id = match segment {
+                "." => id,
+                ".." => self.parent(id).unwrap_or(id),
+                name => match self.child(id, name) {
+                    Some(child) if self.is_dir(child) => child,
+                    Some(child) if !dir_only && segments.peek().is_none() => child,
+                    Some(child) => {
+                        let what = if self.kind(child) == Kind::Link { "symlink" } else { "file" };
+                        return Err((segment, format!("`{name}` in {} is a {what}", self.path(id))));
+                    }
+                    None if !dir_only && segments.peek().is_none() => {
+                        return Err((segment, format!("no `{name}` listed in {}", self.path(id))))
+                    }
+                    None => return Err((segment, format!("no directory `{name}` listed in {}", self.path(id)))),
+                },
+            };
+        }
+
+        Ok(id)
+    }
+
+    fn ls(&mut self, dir: NodeId, list_item: Vec<(usize, &str)>) -> Result<()> {
+        for (line_no, item) in list_item {
+            let listed = match parse_listing(item) {
+                Ok(Listing::Entry(listed)) => listed,
+                Ok(Listing::Nothing) => continue,
+                Ok(Listing::Unrecognized(fragment, message)) => {
+                    self.warn(ParseError::at(line_no, item, fragment, format!("{message}, skipped")));
+                    continue;
+                }
+                Err((fragment, message)) => return Err(ParseError::at(line_no, item, fragment, message)),
+            };
+
+            let node_name = listed.name;
+            let node_type = match listed.kind {
+                Kind::Dir => NodeType::Dir(0),
+                Kind::File => NodeType::File(listed.size),
+                Kind::Link => NodeType::Symlink(listed.target.unwrap_or_default().to_string()),
+            };
+
+            // listing a directory again repeats the entries already counted
+            if let Some(listed) = self.child(dir, node_name) {
+                match (&self.nodes[listed.0].node_type, &node_type) {
+                    (NodeType::Dir(_), NodeType::Dir(_)) => continue,
+                    (before, node_type) if before == node_type => continue,
+                    _ => {
+                        let message =
+                            format!("`{node_name}` was listed before with another type or size");
+                        return Err(ParseError::at(line_no, item, node_name, message));
+                    }
+                }
+            }
+
+            // every directory holds at most what the root does, so a root total
+            // that fits keeps all of them in range
+            if let NodeType::File(node_size) = node_type {
+                if self.used().checked_add(node_size).is_none() {
+                    return Err(ParseError::line(line_no, item, "total size overflows 64 bits"));
+                }
+            }
+
+            self.add_child(dir, node_name, node_type);
+        }
+
+        Ok(())
+    }
+
+    fn add_root(&mut self, name: &str) -> NodeId {
+        self.nodes.push(Node::new(name, NodeType::Dir(0)));
+        NodeId(0)
+    }
+
+    // Adds `name` under `parent` and its size to every directory above it.
+    fn add_child(&mut self, parent: NodeId, name: &str, node_type: NodeType) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        let mut node = Node::new(name, node_type);
+        let child_size = node.total_size();
+
+        let mut current_node = Some(parent);
+        while let Some(node) = current_node {
+            if let NodeType::Dir(ref mut size) = self.nodes[node.0].node_type {
+                *size += child_size;
+            }
+            current_node = self.nodes[node.0].parent;
+        }
+
+        node.parent = Some(parent);
+        self.nodes.push(node);
+        self.nodes[parent.0].children.push(id);
+        self.by_name.insert((parent, name.to_string()), id);
+
+        id
+    }
+
+    pub fn root(&self) -> Option<NodeId> {
+        (!self.nodes.is_empty()).then_some(NodeId(0))
+    }
+
+    // The directory the transcript ended in.
+    pub fn current_dir(&self) -> Option<NodeId> {
+        self.current_dir
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.nodes[id.0].name
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].parent
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.nodes[id.0].children
+    }
+
+    pub fn child(&self, id: NodeId, name: &str) -> Option<NodeId> {
+        self.by_name.get(&(id, name.to_string())).copied()
+    }
+
+    pub fn is_dir(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id.0].node_type, NodeType::Dir(_))
+    }
+
+    pub fn kind(&self, id: NodeId) -> Kind {
+        match self.nodes[id.0].node_type {
+            NodeType::Dir(_) => Kind::Dir,
+            NodeType::File(_) => Kind::File,
+            NodeType::Symlink(_) => Kind::Link,
+        }
+    }
+
+    // Where a symlink points, `None` for anything else.
+    pub fn target(&self, id: NodeId) -> Option<&str> {
+        match &self.nodes[id.0].node_type {
+            NodeType::Symlink(target) => Some(target),
+            _ => None,
+        }
+    }
+
+    // A file's size, or everything under a directory.
+    pub fn size(&self, id: NodeId) -> u64 {
+        self.nodes[id.0].total_size()
+    }
+
+    // `/a/e` from a root named `/`, the names joined by `/` otherwise.
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = vec![self.name(id)];
+        let mut parent = self.parent(id);
+
+        while let Some(node) = parent {
+            names.push(self.name(node));
+            parent = self.parent(node);
+        }
+
+        names.reverse();
+        match names.split_first() {
+            Some((&"/", rest)) => format!("/{}", rest.join("/")),
+            _ => names.join("/"),
+        }
+    }
+
+    // The nodes from `from` down matching `filter`, in listing order.
+    fn filter_nodes(&self, from: NodeId, filter: &dyn Fn(&Node) -> bool) -> Vec<NodeId> {
+        let mut results = Vec::new();
+        let mut stack = vec![from];
+
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id.0];
+            if filter(node) {
+                results.push(id);
+            }
+            stack.extend(node.children.iter().rev());
+        }
+
+        results
+    }
+}
+
+impl Node {
+    fn new(name: &str, node_type: NodeType) -> Node {
+        Node {
+            name: name.to_string(),
+            node_type,
+            parent: None,
+            children: vec![],
+        }
+    }
+
+    fn total_size(&self) -> u64 {
+        match self.node_type {
+            NodeType::File(size) => size,
+            NodeType::Dir(size) => size,
+            NodeType::Symlink(_) => 0,
+        }
+    }
+}
+
+pub fn solve_part1(fs: &FileSystem) -> u64 {
+    fn is_the_dir(node: &Node) -> bool {
+        if let NodeType::Dir(size) = node.node_type {
+            size <= 100000
+        } else {
+            false
+        }
+    }
+
+    let result = fs.filter_nodes(fs.root().unwrap(), &is_the_dir);
+    let total_size = result.iter().fold(0, |acc, &id| acc + fs.size(id));
+
+    total_size
+}
+
+pub fn solve_part2(fs: &FileSystem) -> std::result::Result<Option<u64>, CapacityError> {
+    const TOTAL_SPACE: u64 = 70000000;
+    const UNUSED_SPACE_NEEDED: u64 = 30000000;
+
+    smallest_to_delete(fs, TOTAL_SPACE, UNUSED_SPACE_NEEDED)
+}
+
+// The size of the smallest directory to delete for `required` to be free,
+// `None` when no directory frees enough.
+fn smallest_to_delete(fs: &FileSystem, capacity: u64, required: u64) -> std::result::Result<Option<u64>, CapacityError> {
+    let to_free = fs.plan_needs(capacity, required)?;
+    Ok(fs.smallest_directory(to_free).map(|dir| dir.size))
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input<'a> = FileSystem;
+
+    fn parse(input: &str) -> Result<FileSystem> {
+        let mut fs = FileSystem::new();
+        fs.parse_cmds(input)?;
+
+        if fs.root().is_none() {
+            let text = input.lines().find(|line| !line.trim().is_empty()).unwrap_or_default();
+            return Err(ParseError::line(1, text, "the transcript never enters a directory"));
+        }
+
+        Ok(fs)
+    }
+
+    fn part1(fs: &FileSystem) -> Answer {
+        solve_part1(fs).into()
+    }
+
+    fn part2(fs: &FileSystem) -> Answer {
+        match solve_part2(fs) {
+            Ok(Some(size)) => size.into(),
+            Ok(None) => Answer::Failed("no directory frees enough space".to_string()),
+            Err(e) => Answer::Failed(e.to_string()),
+        }
+    }
+
+    fn warnings(fs: &FileSystem) -> &[ParseError] {
+        fs.warnings()
+    }
+}
+
+// The puzzle's example, for the tests of every module.
+#[cfg(test)]
+pub(crate) fn example() -> FileSystem {
+    let mut fs = FileSystem::new();
+    fs.parse_cmds(include_str!("../example.txt")).unwrap();
+    fs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Helper function to set up a basic filesystem for testing
+    fn setup_filesystem() -> FileSystem {
+        let mut fs = FileSystem::new();
+        let root = fs.add_root("/");
+
+        // Construct the filesystem
+        fs.add_child(root, "dir1", NodeType::Dir(0));
+        fs.add_child(root, "file1", NodeType::File(500));
+        fs.add_child(root, "file2", NodeType::File(2000));
+
+        fs
+    }
+
+    #[test]
+    fn add_child_nodes() {
+        let mut fs = FileSystem::new();
+        let root_node = fs.add_root("/");
+
+        fs.add_child(root_node, "dir1", NodeType::Dir(0));
+
+        assert_eq!(1, fs.children(root_node).len());
+    }
+
+    #[test]
+    fn check_parent_child_relationships() {
+        let mut fs = FileSystem::new();
+        let root_node = fs.add_root("/");
+        let dir1 = fs.add_child(root_node, "dir1", NodeType::Dir(0));
+        let file1 = fs.add_child(root_node, "file1", NodeType::File(122));
+
+        assert_eq!("/", fs.name(fs.parent(dir1).unwrap()));
+        assert_eq!("/", fs.name(fs.parent(file1).unwrap()));
+        assert_eq!(Some(file1), fs.child(root_node, "file1"));
+    }
+
+    #[test]
+    fn test_total_size_calculation() {
+        let mut fs = FileSystem::new();
+        let root_node = fs.add_root("/");
+        fs.add_child(root_node, "dir1", NodeType::Dir(0));
+        let file1 = fs.add_child(root_node, "file1", NodeType::File(122));
+
+        assert_eq!(122, fs.size(file1));
+        assert_eq!(122, fs.size(root_node));
+    }
+
+    #[test]
+    fn modify_parent_and_verify() {
+        let mut fs = FileSystem::new();
+        let root = fs.add_root("/");
+        let dir1 = fs.add_child(root, "dir1", NodeType::Dir(0));
+        let file1 = fs.add_child(root, "file1", NodeType::File(122));
+
+        fs.nodes[file1.0].parent = Some(dir1);
+
+        assert_eq!("dir1", fs.name(fs.parent(file1).unwrap()));
+    }
+
+    #[test]
+    fn nested_dir_size() {
+        let mut fs = FileSystem::new();
+        let root = fs.add_root("root");
+        let dir1 = fs.add_child(root, "dir1", NodeType::Dir(0));
+        let dir2 = fs.add_child(root, "dir2", NodeType::Dir(0));
+
+        fs.add_child(dir1, "file1", NodeType::File(50));
+        fs.add_child(dir2, "file2", NodeType::File(150));
+
+        assert_eq!(200, fs.size(root));
+        assert_eq!("root/dir2", fs.path(dir2));
+    }
+
+    #[test]
+    fn filter_directories() {
+        let fs = setup_filesystem();
+
+        fn is_directory(node: &Node) -> bool {
+            matches!(node.node_type, NodeType::Dir(_))
+        }
+
+        let directories = fs.filter_nodes(fs.root().unwrap(), &is_directory);
+
+        assert_eq!(directories, vec![NodeId(0), NodeId(1)]); // root and dir1
+        assert_eq!(fs.children(fs.root().unwrap()).len(), 3);
+    }
+
+    #[test]
+    fn filter_files() {
+        let fs = setup_filesystem();
+
+        fn is_file(node: &Node) -> bool {
+            matches!(node.node_type, NodeType::File(_))
+        }
+
+        let files = fs.filter_nodes(fs.root().unwrap(), &is_file);
+
+        assert_eq!(files.len(), 2); // file1 and file2
+    }
+
+    #[test]
+    fn filter_large_files() {
+        let fs = setup_filesystem();
+
+        fn is_large_file(node: &Node) -> bool {
+            if let NodeType::File(size) = node.node_type {
+                size > 1000
+            } else {
+                false
+            }
+        }
+
+        let large_files = fs.filter_nodes(fs.root().unwrap(), &is_large_file);
+
+        assert_eq!(large_files.len(), 1); // only file2
+    }
+
+    #[test]
+    fn build_a_large_flat_directory() {
+        let mut transcript = String::from("$ cd /\n$ ls\n");
+        for i in 0..50000 {
+            transcript.push_str(&format!("{} f{i}\n", i % 7));
+        }
+
+        let mut fs = FileSystem::new();
+        fs.parse_cmds(&transcript).unwrap();
+        fs.parse_cmds(&transcript).unwrap();
+
+        assert_eq!(50001, fs.nodes.len());
+        assert_eq!(149997, fs.size(fs.root().unwrap()));
+    }
+
+    #[test]
+    fn parse_and_get_size() {
+        let line = "$ cd /
+$ ls
+dir a
+5 b.txt
+5 c.dat
+dir d";
+
+        let mut fs = FileSystem::new();
+
+        fs.parse_cmds(line).unwrap();
+
+        assert_eq!(10, fs.size(fs.root().unwrap()));
+    }
+
+    #[test]
+    fn test_ls_output() {
+        let line = "$ cd /
+$ ls
+dir a
+14848514 b.txt";
+
+        let mut fs = FileSystem::new();
+
+        fs.parse_cmds(line).unwrap();
+        dbg!(&fs);
+
+        // Assuming you have a method to get the number of children in the current directory
+        assert_eq!(2, fs.children(fs.root().unwrap()).len());
+    }
+
+    #[test]
+    fn test_back_navigation() {
+        let line = "$ cd dir1
+$ ls
+dir dir2
+$ cd dir2
+$ cd ..";
+
+        let mut fs = FileSystem::new();
+
+        fs.parse_cmds(line).unwrap();
+
+        dbg!(&fs);
+
+        assert_eq!("dir1", fs.name(fs.current_dir.unwrap()));
+    }
+
+    #[test]
+    fn report_bad_transcript() {
+        let mut fs = FileSystem::new();
+        fs.parse_cmds("$ cd /\n$ ls\nabc b.txt\n$ pwd\n/").unwrap();
+        let warnings: Vec<_> = fs.warnings().iter().map(|w| (w.line, w.column, w.text.as_str())).collect();
+        assert_eq!(vec![(3, 1, "abc"), (4, 3, "pwd")], warnings);
+
+        let mut fs = FileSystem::new();
+        assert!(fs.parse_cmds("$ ls\n5 a").is_err());
+    }
+
+    #[test]
+    fn reject_transcripts_without_a_directory() {
+        for input in ["", "$ pwd\n/home"] {
+            let error = Day7::parse(input).unwrap_err();
+            assert_eq!("the transcript never enters a directory", error.message);
+        }
+    }
+
+    #[test]
+    fn tolerate_real_sessions() {
+        let line = "$ pwd
+/home/bob
+$ cd /
+$ ls -la
+total 20
+drwxr-xr-x  4 bob bob 4096 Jan  2 12:00 .
+drwxr-xr-x  4 bob bob 4096 Jan  2 12:00 ..
+drwxr-xr-x  2 bob bob 4096 Jan  2 12:00 src
+-rw-r--r--  1 bob bob  120 Jan  2 12:00 Cargo.toml
+lrwxrwxrwx  1 bob bob   10 Jan  2 12:00 latest -> src/lib.rs
+$ cat Cargo.toml
+[package]
+$ ls src missing
+ls: cannot access 'missing': No such file or directory
+$ ls src
+300 lib.rs
+ls: cannot open directory 'src/private': Permission denied";
+
+        let mut fs = FileSystem::new();
+        fs.parse_cmds(line).unwrap();
+
+        assert_eq!(420, fs.used());
+        let latest = fs.child(fs.root().unwrap(), "latest").unwrap();
+        assert_eq!((Kind::Link, Some("src/lib.rs")), (fs.kind(latest), fs.target(latest)));
+
+        let warnings: Vec<_> = fs.warnings().iter().map(|w| (w.line, w.message.as_str())).collect();
+        assert_eq!(
+            vec![
+                (1, "`pwd` skipped"),
+                (11, "`cat` skipped"),
+                (13, "`ls` skipped"),
+                (17, "`ls` reported an error, skipped"),
+            ],
+            warnings
+        );
+
+        let error = fs.parse_cmds("$ cd latest").unwrap_err();
+        assert_eq!("`latest` in / is a symlink", error.message);
+    }
+
+    #[test]
+    fn plug_in_recognizers() {
+        struct Cmd;
+
+        impl Recognizer for Cmd {
+            fn recognize<'l>(&self, command: &'l str) -> Option<Command<'l>> {
+                match command.split_once(' ') {
+                    Some(("chdir", path)) => Some(Command::Cd(path)),
+                    None if command == "dir" => Some(Command::Ls(None)),
+                    _ => None,
+                }
+            }
+        }
+
+        let mut fs = FileSystem::new();
+        fs.parse_with("$ chdir /\n$ dir\n5 a\n$ ls\n7 b", &[&Cmd]).unwrap();
+        assert_eq!(5, fs.used());
+        assert_eq!(1, fs.warnings().len());
+    }
+
+    #[test]
+    fn cd_follows_paths() {
+        let line = "$ cd /
+$ ls
+dir a
+1 x
+$ cd a
+$ ls
+dir b
+$ cd b
+$ ls
+dir c
+$ cd /a/b/c
+$ cd ../../b/./c
+$ cd ../..";
+
+        let mut fs = FileSystem::new();
+        fs.parse_cmds(line).unwrap();
+        assert_eq!("/a", fs.path(fs.current_dir.unwrap()));
+
+        fs.parse_cmds("$ cd /
+$ cd ..
+$ cd a/b/c").unwrap();
+        assert_eq!("/a/b/c", fs.path(fs.current_dir.unwrap()));
+        assert_eq!(2, fs.children(fs.root().unwrap()).len());
+
+        let error = fs.parse_cmds("$ cd /a/z").unwrap_err();
+        assert_eq!((1, 9, "no directory `z` listed in /a"), (error.line, error.column, error.message.as_str()));
+
+        let error = fs.parse_cmds("$ cd /x").unwrap_err();
+        assert_eq!("`x` in / is a file", error.message);
+    }
+
+    #[test]
+    fn repeated_ls_counts_once() {
+        let line = "$ cd /
+$ ls
+dir a
+5 b.txt
+$ cd a
+$ ls
+7 c
+$ cd /
+$ ls
+dir a
+5 b.txt
+$ cd a
+$ ls
+7 c";
+
+        let mut fs = FileSystem::new();
+        fs.parse_cmds(line).unwrap();
+
+        assert_eq!(12, fs.size(fs.root().unwrap()));
+        assert_eq!(2, fs.children(fs.root().unwrap()).len());
+
+        let error = fs.parse_cmds("$ cd /
+$ ls
+6 b.txt").unwrap_err();
+        assert_eq!((3, 3), (error.line, error.column));
+    }
+
+    #[test]
+    fn sizes_past_u32_and_overflow() {
+        let mut fs = FileSystem::new();
+        fs.parse_cmds("$ cd /\n$ ls\n5000000000 big\n4000000000 bigger").unwrap();
+        assert_eq!(9000000000, fs.size(fs.root().unwrap()));
+        assert_eq!(
+            "disk already over capacity: 9000000000 used out of 70000000",
+            solve_part2(&fs).unwrap_err().to_string()
+        );
+        assert_eq!(
+            Answer::Failed("disk already over capacity: 9000000000 used out of 70000000".to_string()),
+            Day7::part2(&fs)
+        );
+
+        let mut fs = FileSystem::new();
+        let error = fs.parse_cmds("$ cd /\n$ ls\n18446744073709551615 a\n1 b").unwrap_err();
+        assert_eq!((4, 1), (error.line, error.column));
+        assert_eq!("total size overflows 64 bits", error.message);
+
+        let mut fs = FileSystem::new();
+        let error = fs.parse_cmds("$ cd /\n$ ls\n18446744073709551616 a").unwrap_err();
+        assert_eq!("file size does not fit in 64 bits", error.message);
+    }
+
+    #[test]
+    fn part1() {
+        let line = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+        let mut fs = FileSystem::new();
+
+        fs.parse_cmds(line).unwrap();
+
+        fn is_the_dir(node: &Node) -> bool {
+            match node.node_type {
+                NodeType::Dir(size) => size <= 100000,
+                NodeType::File(_) | NodeType::Symlink(_) => false,
+            }
+        }
+
+        // dbg!(&fs);
+
+        let result = fs.filter_nodes(fs.root().unwrap(), &is_the_dir);
+        let total_size = result.iter().fold(0, |acc, &id| acc + fs.size(id));
+
+        assert_eq!(95437, total_size);
+        assert_eq!(2, result.len());
+    }
+
+    #[test]
+    fn part2() {
+        let line = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+        let mut fs = FileSystem::new();
+
+        fs.parse_cmds(line).unwrap();
+
+        const TOTAL_SPACE: u64 = 70000000;
+        const UNUSED_SPACE_NEEDED: u64 = 30000000;
+
+        let current_empty_space: u64 = TOTAL_SPACE - fs.size(fs.root().unwrap());
+
+        let is_the_dir = |node: &Node| -> bool {
+            match node.node_type {
+                NodeType::Dir(size) => (size + current_empty_space) >= UNUSED_SPACE_NEEDED,
+                NodeType::File(_) | NodeType::Symlink(_) => false,
+            }
+        };
+
+        let result = fs.filter_nodes(fs.root().unwrap(), &is_the_dir);
+
+        let smallest_node = result.iter().min_by(|&&a, &&b| {
+            fs.size(a).cmp(&fs.size(b))
+        });
+
+        assert_eq!(24933642, fs.size(*smallest_node.unwrap()));
+        assert_eq!(Answer::Number(24933642), Day7::part2(&fs));
+    }
+
+    #[test]
+    fn nothing_large_enough_to_delete() {
+        let mut fs = FileSystem::new();
+        fs.parse_cmds("$ cd /\n$ ls\ndir a\n10 b\n$ cd a\n$ ls\n20 c").unwrap();
+
+        assert_eq!(Ok(Some(20)), smallest_to_delete(&fs, 100, 90));
+        assert_eq!(Ok(None), smallest_to_delete(&fs, 100, 110));
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::report::child_path;
+use crate::{Entry, FileSystem, Kind, NodeId, View};
+
+// What to delete so that `required` bytes are free on a disk of `capacity`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub capacity: u64,
+    pub required: u64,
+    pub used: u64,
+    pub to_free: u64,
+    // The smallest directory freeing enough on its own.
+    pub directory: Option<Entry>,
+    pub selection: Option<Selection>,
+}
+
+// Directories and files, none inside another, freeing enough.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub entries: Vec<Entry>,
+    // Whether nothing frees enough with a smaller total. Past `MAX_SEARCH`
+    // bytes the largest files are taken first instead, which may free more.
+    pub smallest: bool,
+}
+
+impl Plan {
+    pub fn selection_size(&self) -> Option<u64> {
+        self.selection
+            .as_ref()
+            .map(|selection| selection.entries.iter().map(|entry| entry.size).sum())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CapacityError {
+    OverCapacity { used: u64, capacity: u64 },
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapacityError::OverCapacity { used, capacity } => {
+                write!(f, "disk already over capacity: {used} used out of {capacity}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let free = self.capacity - self.used;
+        writeln!(f, "capacity {}, used {}, free {free}", self.capacity, self.used)?;
+
+        if self.to_free == 0 {
+            return writeln!(f, "{} must be free, nothing needs to be deleted", self.required);
+        }
+        writeln!(
+            f,
+            "{} must be free, so at least {} - {free} = {} must be deleted",
+            self.required, self.required, self.to_free
+        )?;
+
+        match &self.directory {
+            Some(dir) => writeln!(
+                f,
+                "smallest directory: {} ({}), {} more than needed",
+                dir.path,
+                dir.size,
+                dir.size - self.to_free
+            )?,
+            None => writeln!(f, "smallest directory: none is large enough")?,
+        }
+
+        match (&self.selection, self.selection_size()) {
+            (Some(selection), Some(size)) => {
+                let name = match selection.smallest {
+                    true => "smallest selection",
+                    false => "selection, largest files first as the search would be too large",
+                };
+                writeln!(
+                    f,
+                    "{name}: {} entries, {size} in total, {} more than needed",
+                    selection.entries.len(),
+                    size - self.to_free
+                )?;
+                for entry in &selection.entries {
+                    let kind = entry.kind.name();
+                    writeln!(f, "  {kind:<4}  {:>10}  {}", entry.size, entry.path)?;
+                }
+                Ok(())
+            }
+            _ => writeln!(f, "smallest selection: the whole disk does not free enough"),
+        }
+    }
+}
+
+impl FileSystem {
+    pub fn plan(&self, capacity: u64, required: u64) -> Result<Plan, CapacityError> {
+        let to_free = self.plan_needs(capacity, required)?;
+
+        Ok(Plan {
+            capacity,
+            required,
+            used: self.used(),
+            to_free,
+            directory: self.smallest_directory(to_free),
+            selection: self.smallest_selection(to_free),
+        })
+    }
+
+    pub fn used(&self) -> u64 {
+        self.root().map_or(0, |root_dir| self.size(root_dir))
+    }
+
+    // Bytes to delete for `required` to be free on a disk of `capacity`.
+    pub fn plan_needs(&self, capacity: u64, required: u64) -> Result<u64, CapacityError> {
+        let used = self.used();
+        let free = capacity
+            .checked_sub(used)
+            .ok_or(CapacityError::OverCapacity { used, capacity })?;
+
+        Ok(required.saturating_sub(free))
+    }
+
+    // The smallest directory of at least `to_free`, `/` included.
+    pub fn smallest_directory(&self, to_free: u64) -> Option<Entry> {
+        self.report(View::List, None)
+            .into_iter()
+            .filter(|entry| entry.kind == Kind::Dir && entry.size >= to_free)
+            .min_by_key(|entry| entry.size)
+    }
+
+    // A directory frees exactly its files, so the best selection is the
+    // smallest subset of files adding up to `to_free`. Directories whose
+    // files are all in it stand for them.
+    pub fn smallest_selection(&self, to_free: u64) -> Option<Selection> {
+        let root_dir = self.root()?;
+
+        let files: Vec<Entry> = self
+            .report(View::List, None)
+            .into_iter()
+            .filter(|entry| entry.kind == Kind::File && entry.size > 0)
+            .collect();
+        let sizes: Vec<u64> = files.iter().map(|file| file.size).collect();
+
+        let (picked, smallest) = smallest_sum_at_least(&sizes, to_free)?;
+        let chosen: HashSet<&str> = picked.iter().map(|&i| files[i].path.as_str()).collect();
+
+        Some(Selection {
+            entries: compact(self, root_dir, &self.path(root_dir), 0, &chosen).1,
+            smallest,
+        })
+    }
+}
+
+// Whether every file under `id` is chosen, and the chosen entries with
+// fully chosen directories in place of their content.
+fn compact(fs: &FileSystem, id: NodeId, path: &str, depth: usize, chosen: &HashSet<&str>) -> (bool, Vec<Entry>) {
+    if !fs.is_dir(id) {
+        return match fs.size(id) {
+            0 => (true, vec![]),
+            _ if chosen.contains(path) => (true, vec![fs.entry(id, path, depth)]),
+            _ => (false, vec![]),
+        };
+    }
+
+    let mut all = true;
+    let mut entries = vec![];
+
+    for &child in fs.children(id) {
+        let (child_all, child_entries) = compact(fs, child, &child_path(path, fs.name(child)), depth + 1, chosen);
+        all &= child_all;
+        entries.extend(child_entries);
+    }
+
+    if all && fs.size(id) > 0 {
+        entries = vec![fs.entry(id, path, depth)];
+    }
+
+    (all, entries)
+}
+
+// Indices of the subset of `sizes` with the smallest sum of at least
+// `target`, `None` when even all of them fall short. The flag is false when
+// the sums to search pass `MAX_SEARCH` and the largest sizes are taken
+// first instead, which reaches `target` but may overshoot more.
+fn smallest_sum_at_least(sizes: &[u64], target: u64) -> Option<(Vec<usize>, bool)> {
+    let total: u64 = sizes.iter().sum();
+    if total < target {
+        return None;
+    }
+    if target == 0 {
+        return Some((vec![], true));
+    }
+
+    // without any one of its items the best subset falls under `target`, so
+    // its sum stays under `target` plus the largest size, and it is no larger
+    // than any single size reaching `target`
+    let max = sizes.iter().max().copied().unwrap_or(0);
+    let single = sizes.iter().copied().filter(|&size| size >= target).min().unwrap_or(total);
+    let limit = target.saturating_add(max).min(total).min(single);
+    if limit > MAX_SEARCH {
+        return Some((largest_first(sizes, target), false));
+    }
+    let limit = limit as usize;
+
+    // the item first reaching each sum, found with a bitset of reachable sums
+    const UNREACHED: u32 = u32::MAX;
+    let mut reached_by = vec![UNREACHED; limit + 1];
+    let mut reachable = vec![0u64; limit / 64 + 1];
+    reachable[0] = 1;
+
+    for (i, &size) in sizes.iter().enumerate() {
+        let size = size as usize;
+        if size == 0 || size > limit {
+            continue;
+        }
+
+        add_to_all(&mut reachable, size, limit, &mut |sum| reached_by[sum] = i as u32);
+    }
+
+    let target = target as usize;
+    let mut sum = (target / 64..reachable.len()).find_map(|w| {
+        let word = if w == target / 64 { reachable[w] >> (target % 64) << (target % 64) } else { reachable[w] };
+        (word != 0).then(|| w * 64 + word.trailing_zeros() as usize)
+    })?;
+    let mut picked = vec![];
+
+    while sum > 0 {
+        let i = reached_by[sum] as usize;
+        picked.push(i);
+        sum -= sizes[i] as usize;
+    }
+
+    Some((picked, true))
+}
+
+// The most sums searched, which keeps `reached_by` to 64 MiB.
+const MAX_SEARCH: u64 = 1 << 24;
+
+// Largest sizes first until `target` is reached.
+fn largest_first(sizes: &[u64], target: u64) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(sizes[i]));
+
+    let mut sum = 0;
+    order
+        .into_iter()
+        .take_while(|&i| {
+            let short = sum < target;
+            sum += sizes[i];
+            short
+        })
+        .collect()
+}
+
+// Marks every reachable sum plus `by` as reachable too, up to `limit`, and
+// calls `reached` with the sums that were not before. Going from the high
+// words down only reads words that are still unchanged.
+fn add_to_all(bits: &mut [u64], by: usize, limit: usize, reached: &mut dyn FnMut(usize)) {
+    let (words, offset) = (by / 64, by % 64);
+    let last_bits = limit % 64 + 1;
+
+    for w in (words..bits.len()).rev() {
+        let mut shifted = bits[w - words] << offset;
+        if offset > 0 && w > words {
+            shifted |= bits[w - words - 1] >> (64 - offset);
+        }
+        if w == bits.len() - 1 && last_bits < 64 {
+            shifted &= (1 << last_bits) - 1;
+        }
+
+        let mut new = shifted & !bits[w];
+        bits[w] |= shifted;
+        while new != 0 {
+            reached(w * 64 + new.trailing_zeros() as usize);
+            new &= new - 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_the_example() {
+        let fs = crate::example();
+
+        let plan = fs.plan(70000000, 30000000).unwrap();
+        assert_eq!(8381165, plan.to_free);
+        assert_eq!(Some(("/d", 24933642)), plan.directory.as_ref().map(|dir| (dir.path.as_str(), dir.size)));
+
+        // 8504156 alone beats every other combination reaching 8381165
+        let selection: Vec<&str> = plan.selection.as_ref().unwrap().entries.iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(vec!["/c.dat"], selection);
+
+        let plan = fs.plan(70000000, 70000001).unwrap();
+        assert_eq!(None, plan.directory);
+        assert_eq!(None, plan.selection);
+
+        let plan = fs.plan(48381165, 94853 + 8504156).unwrap();
+        let selection: Vec<&str> = plan.selection.as_ref().unwrap().entries.iter().map(|entry| entry.path.as_str()).collect();
+        assert_eq!(vec!["/a", "/c.dat"], selection);
+        assert!(plan.to_string().contains("smallest selection: 2 entries, 8599009 in total, 0 more than needed"));
+
+        assert_eq!(
+            Err(CapacityError::OverCapacity { used: 48381165, capacity: 48381164 }),
+            fs.plan(48381164, 0)
+        );
+    }
+
+    #[test]
+    fn find_smallest_sums() {
+        let sum = |(picked, smallest): (Vec<usize>, bool), sizes: &[u64]| {
+            assert!(smallest);
+            picked.iter().map(|&i| sizes[i]).sum::<u64>()
+        };
+
+        let sizes = [70, 65, 64, 3, 1];
+        assert_eq!(68, sum(smallest_sum_at_least(&sizes, 68).unwrap(), &sizes));
+        assert_eq!(129, sum(smallest_sum_at_least(&sizes, 129).unwrap(), &sizes));
+        assert_eq!(200, sum(smallest_sum_at_least(&sizes, 200).unwrap(), &sizes));
+        assert_eq!(None, smallest_sum_at_least(&sizes, 204));
+        assert_eq!(Some((vec![], true)), smallest_sum_at_least(&sizes, 0));
+
+        // 6 + 2 would do, but the search stops short of sums this large
+        let sizes = [6 << 30, 5 << 30, 2 << 30];
+        let (picked, smallest) = smallest_sum_at_least(&sizes, 7 << 30).unwrap();
+        assert!(!smallest);
+        assert!(picked.iter().map(|&i| sizes[i]).sum::<u64>() >= 7 << 30);
+    }
+}
@@ -0,0 +1,461 @@
+use std::str::FromStr;
+
+use common::{ParseError, Result};
+
+use crate::Entry;
+
+// A filter over the entries of the filesystem:
+//
+//   type=dir and size<=100000 and depth>1 and not name~"*.log"
+//
+// Fields are `type` (`dir`, `file` or `link`), `name` and `path`, compared
+// with `=`, `!=` or the glob match `~`, and `size` and `depth`, compared with
+// `=`, `!=`, `<`, `<=`, `>` or `>=`. Numbers may be sums such as `used-40000000`, where
+// `used` is the size of the root directory. `and` binds tighter than `or`,
+// parentheses group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query(Expr);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Text(TextField, Op, String),
+    Number(NumberField, Op, Vec<Term>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextField {
+    Type,
+    Name,
+    Path,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberField {
+    Size,
+    Depth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Glob,
+}
+
+// A signed part of a number sum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Term {
+    Number(i64),
+    Used(i64),
+}
+
+impl Query {
+    // Whether `entry` matches, `used` being the size of the root directory.
+    pub fn matches(&self, entry: &Entry, used: u64) -> bool {
+        self.0.matches(entry, used)
+    }
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(query: &str) -> Result<Query> {
+        let mut parser = Parser { source: query, rest: query };
+
+        let expr = parser.or()?;
+        parser.skip_spaces();
+        if !parser.rest.is_empty() {
+            return Err(parser.error("expected `and`, `or` or the end of the query"));
+        }
+
+        Ok(Query(expr))
+    }
+}
+
+impl Expr {
+    fn matches(&self, entry: &Entry, used: u64) -> bool {
+        match self {
+            Expr::And(a, b) => a.matches(entry, used) && b.matches(entry, used),
+            Expr::Or(a, b) => a.matches(entry, used) || b.matches(entry, used),
+            Expr::Not(a) => !a.matches(entry, used),
+            Expr::Text(field, op, value) => {
+                let actual = match field {
+                    TextField::Type => entry.kind.name(),
+                    TextField::Name => &entry.name,
+                    TextField::Path => &entry.path,
+                };
+
+                match op {
+                    Op::Eq => actual == value,
+                    Op::Ne => actual != value,
+                    _ => glob(value, actual),
+                }
+            }
+            Expr::Number(field, op, terms) => {
+                let actual = field.value(entry) as i128;
+                let expected: i128 = terms
+                    .iter()
+                    .map(|term| match *term {
+                        Term::Number(n) => n as i128,
+                        Term::Used(sign) => sign as i128 * used as i128,
+                    })
+                    .sum();
+
+                match op {
+                    Op::Eq => actual == expected,
+                    Op::Ne => actual != expected,
+                    Op::Lt => actual < expected,
+                    Op::Le => actual <= expected,
+                    Op::Gt => actual > expected,
+                    Op::Ge => actual >= expected,
+                    Op::Glob => unreachable!("rejected by the parser"),
+                }
+            }
+        }
+    }
+}
+
+impl NumberField {
+    fn value(self, entry: &Entry) -> u64 {
+        match self {
+            NumberField::Size => entry.size,
+            NumberField::Depth => entry.depth as u64,
+        }
+    }
+}
+
+// How the matching entries are summed up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Count,
+    Sum(NumberField),
+    MinBy(NumberField),
+    MaxBy(NumberField),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Aggregated {
+    Number(u128),
+    Entry(Option<Entry>),
+}
+
+impl Aggregate {
+    pub fn apply(self, entries: &[Entry]) -> Aggregated {
+        match self {
+            Aggregate::Count => Aggregated::Number(entries.len() as u128),
+            // nested directories count their content again, so this can pass 64 bits
+            Aggregate::Sum(field) => Aggregated::Number(entries.iter().map(|entry| field.value(entry) as u128).sum()),
+            Aggregate::MinBy(field) => Aggregated::Entry(entries.iter().min_by_key(|entry| field.value(entry)).cloned()),
+            Aggregate::MaxBy(field) => Aggregated::Entry(entries.iter().max_by_key(|entry| field.value(entry)).cloned()),
+        }
+    }
+}
+
+impl FromStr for Aggregate {
+    type Err = String;
+
+    fn from_str(aggregate: &str) -> std::result::Result<Aggregate, String> {
+        let field = |name: &str| match name {
+            "size" => Ok(NumberField::Size),
+            "depth" => Ok(NumberField::Depth),
+            _ => Err(format!("cannot aggregate `{name}`, expected size or depth")),
+        };
+
+        let Some((function, rest)) = aggregate.split_once('(') else {
+            return match aggregate {
+                "count" => Ok(Aggregate::Count),
+                _ => Err(format!("unknown aggregate `{aggregate}`, expected count, sum(F), min_by(F) or max_by(F)")),
+            };
+        };
+        let name = rest
+            .strip_suffix(')')
+            .ok_or_else(|| format!("missing `)` in `{aggregate}`"))?;
+
+        match function {
+            "sum" => Ok(Aggregate::Sum(field(name)?)),
+            "min_by" => Ok(Aggregate::MinBy(field(name)?)),
+            "max_by" => Ok(Aggregate::MaxBy(field(name)?)),
+            _ => Err(format!("unknown aggregate `{function}`, expected count, sum, min_by or max_by")),
+        }
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+
+        if self.symbol("(") {
+            let expr = self.or()?;
+            if !self.symbol(")") {
+                return Err(self.error("expected `)`"));
+            }
+            return Ok(expr);
+        }
+
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        self.skip_spaces();
+        let field = self.word();
+
+        let text_field = match field {
+            "type" => Some(TextField::Type),
+            "name" => Some(TextField::Name),
+            "path" => Some(TextField::Path),
+            "size" | "depth" => None,
+            _ => return Err(self.error_at(field, "expected type, name, path, size or depth")),
+        };
+
+        self.skip_spaces();
+        let op_text = self.rest;
+        let op = ["<=", ">=", "!=", "==", "=", "<", ">", "~"]
+            .into_iter()
+            .find(|op| self.symbol(op))
+            .map(|op| match op {
+                "<=" => Op::Le,
+                ">=" => Op::Ge,
+                "!=" => Op::Ne,
+                "<" => Op::Lt,
+                ">" => Op::Gt,
+                "~" => Op::Glob,
+                _ => Op::Eq,
+            })
+            .ok_or_else(|| self.error("expected a comparison"))?;
+
+        match text_field {
+            Some(text_field) => {
+                if matches!(op, Op::Lt | Op::Le | Op::Gt | Op::Ge) || (text_field == TextField::Type && op == Op::Glob) {
+                    return Err(self.error_at(&op_text[..1], format!("`{field}` cannot be compared that way")));
+                }
+
+                self.skip_spaces();
+                let value_text = self.rest;
+                let value = self.text()?;
+                if text_field == TextField::Type && !["dir", "file", "link"].contains(&value.as_str()) {
+                    return Err(self.error_at(value_text, "expected dir, file or link"));
+                }
+
+                Ok(Expr::Text(text_field, op, value))
+            }
+            None => {
+                if op == Op::Glob {
+                    return Err(self.error_at(&op_text[..1], format!("`{field}` is a number, `~` matches text")));
+                }
+
+                let number_field = if field == "size" { NumberField::Size } else { NumberField::Depth };
+                Ok(Expr::Number(number_field, op, self.sum()?))
+            }
+        }
+    }
+
+    // A quoted string or a bare word up to the next space or `)`.
+    fn text(&mut self) -> Result<String> {
+        if let Some(quoted) = self.rest.strip_prefix('"') {
+            let end = quoted.find('"').ok_or_else(|| self.error("missing closing `\"`"))?;
+            self.rest = &quoted[end + 1..];
+            return Ok(quoted[..end].to_string());
+        }
+
+        let end = self.rest.find(|c: char| c.is_whitespace() || c == ')').unwrap_or(self.rest.len());
+        if end == 0 {
+            return Err(self.error("expected a value"));
+        }
+
+        let (value, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Ok(value.to_string())
+    }
+
+    // NUMBER or `used`, added or subtracted.
+    fn sum(&mut self) -> Result<Vec<Term>> {
+        let mut terms = vec![];
+        let mut sign = 1;
+
+        loop {
+            self.skip_spaces();
+            let digits = self.rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(self.rest.len());
+
+            if digits > 0 {
+                let (number, rest) = self.rest.split_at(digits);
+                let value: i64 = number.parse().map_err(|_| self.error_at(number, "number out of range"))?;
+                self.rest = rest;
+                terms.push(Term::Number(sign * value));
+            } else if self.keyword("used") {
+                terms.push(Term::Used(sign));
+            } else {
+                return Err(self.error("expected a number or `used`"));
+            }
+
+            sign = if self.symbol("+") {
+                1
+            } else if self.symbol("-") {
+                -1
+            } else {
+                return Ok(terms);
+            };
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    fn word(&mut self) -> &'a str {
+        let end = self
+            .rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(self.rest.len());
+        let (word, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        word
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let before = self.rest;
+        self.skip_spaces();
+
+        if self.word() == keyword {
+            true
+        } else {
+            self.rest = before;
+            false
+        }
+    }
+
+    fn symbol(&mut self, symbol: &str) -> bool {
+        self.skip_spaces();
+
+        match self.rest.strip_prefix(symbol) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        let end = self.rest.find(char::is_whitespace).unwrap_or(self.rest.len());
+        self.error_at(&self.rest[..end], message)
+    }
+
+    fn error_at(&self, fragment: &str, message: impl Into<String>) -> ParseError {
+        ParseError::at(1, self.source, fragment, message)
+    }
+}
+
+// `*` matches any run of characters and `?` a single one.
+fn glob(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    // matched[j]: the pattern so far matches the first j characters
+    let mut matched = vec![false; text.len() + 1];
+    matched[0] = true;
+
+    for p in pattern {
+        let previous = matched.clone();
+        matched[0] = previous[0] && p == '*';
+
+        for j in 1..=text.len() {
+            matched[j] = match p {
+                '*' => previous[j] || matched[j - 1],
+                '?' => previous[j - 1],
+                c => previous[j - 1] && text[j - 1] == c,
+            };
+        }
+    }
+
+    matched[text.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(query: &str, aggregate: &str) -> Aggregated {
+        let entries = crate::example().query(&query.parse().unwrap());
+        aggregate.parse::<Aggregate>().unwrap().apply(&entries)
+    }
+
+    #[test]
+    fn parts_as_queries() {
+        assert_eq!(Aggregated::Number(95437), run("type=dir and size<=100000", "sum(size)"));
+
+        let Aggregated::Entry(Some(entry)) = run("type = dir and size >= used - 40000000", "min_by(size)") else {
+            panic!("expected a directory");
+        };
+        assert_eq!(("/d", 24933642), (entry.path.as_str(), entry.size));
+    }
+
+    #[test]
+    fn combine_conditions() {
+        assert_eq!(Aggregated::Number(8), run("name~\"*.l??\" or (depth>1 and not type=dir)", "count"));
+        assert_eq!(Aggregated::Number(2), run("name~\"*.l??\"", "count"));
+        assert_eq!(Aggregated::Number(1), run("path~/a/* and type=dir", "count"));
+
+        let Aggregated::Entry(Some(entry)) = run("type=file", "max_by(size)") else {
+            panic!("expected a file");
+        };
+        assert_eq!("b.txt", entry.name);
+    }
+
+    #[test]
+    fn reject_bad_queries() {
+        let error = "type=dir and sise<5".parse::<Query>().unwrap_err();
+        assert_eq!((14, "expected type, name, path, size or depth"), (error.column, error.message.as_str()));
+
+        let error = "size~1".parse::<Query>().unwrap_err();
+        assert_eq!((5, "`size` is a number, `~` matches text"), (error.column, error.message.as_str()));
+
+        let error = "type=socket".parse::<Query>().unwrap_err();
+        assert_eq!((6, "expected dir, file or link"), (error.column, error.message.as_str()));
+
+        assert!("(size<5".parse::<Query>().is_err());
+        assert!("size<5 size".parse::<Query>().is_err());
+        assert!("median(size)".parse::<Aggregate>().is_err());
+    }
+
+    #[test]
+    fn match_globs() {
+        assert!(glob("*.log", "d.log"));
+        assert!(glob("?", "é"));
+        assert!(!glob("*.log", "d.logs"));
+        assert!(glob("*", ""));
+    }
+}
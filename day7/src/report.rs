@@ -0,0 +1,269 @@
+use std::fmt::Write;
+use std::str::FromStr;
+
+use common::json;
+
+use crate::{FileSystem, NodeId, Query};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Dir,
+    File,
+    Link,
+}
+
+impl Kind {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Kind::Dir => "dir",
+            Kind::File => "file",
+            Kind::Link => "link",
+        }
+    }
+}
+
+// A node met while walking the tree, the root at depth 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub path: String,
+    pub name: String,
+    pub kind: Kind,
+    pub size: u64,
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    // Every node in listing order.
+    Tree,
+    // The same nodes, one line each with their type and size.
+    List,
+    // Directories, largest first.
+    Du,
+    // The N largest directories, then the N largest files.
+    Top(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> std::result::Result<Format, String> {
+        match format {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format `{format}`, expected text, json or csv")),
+        }
+    }
+}
+
+impl FileSystem {
+    // Calls `visitor` on `from` and then on the nodes below it, depth first,
+    // going no deeper than `max_depth` below it.
+    fn visit(&self, from: NodeId, max_depth: Option<usize>, visitor: &mut dyn FnMut(NodeId, &str, usize)) {
+        let mut stack = vec![(from, self.path(from), 0)];
+
+        while let Some((id, path, depth)) = stack.pop() {
+            visitor(id, &path, depth);
+
+            if max_depth.is_some_and(|max_depth| depth >= max_depth) {
+                continue;
+            }
+
+            for &child in self.children(id).iter().rev() {
+                stack.push((child, child_path(&path, self.name(child)), depth + 1));
+            }
+        }
+    }
+
+    pub(crate) fn entry(&self, id: NodeId, path: &str, depth: usize) -> Entry {
+        Entry {
+            path: path.to_string(),
+            name: self.name(id).to_string(),
+            kind: self.kind(id),
+            size: self.size(id),
+            depth,
+        }
+    }
+
+    // The entries shown by `view`, down to `max_depth` below the root.
+    pub fn report(&self, view: View, max_depth: Option<usize>) -> Vec<Entry> {
+        match self.root() {
+            Some(root_dir) => self.report_at(root_dir, view, max_depth),
+            None => vec![],
+        }
+    }
+
+    // The same for the subtree of `from`, which is at depth 0.
+    pub fn report_at(&self, from: NodeId, view: View, max_depth: Option<usize>) -> Vec<Entry> {
+        let mut entries = vec![];
+
+        self.visit(from, max_depth, &mut |id, path, depth| {
+            entries.push(self.entry(id, path, depth));
+        });
+
+        let largest = |entries: &[Entry], kind: Kind| {
+            let mut entries: Vec<Entry> = entries.iter().filter(|entry| entry.kind == kind).cloned().collect();
+            entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+            entries
+        };
+
+        match view {
+            View::Tree | View::List => entries,
+            View::Du => largest(&entries, Kind::Dir),
+            View::Top(n) => {
+                let mut top: Vec<Entry> = largest(&entries, Kind::Dir).into_iter().take(n).collect();
+                top.extend(largest(&entries, Kind::File).into_iter().take(n));
+                top
+            }
+        }
+    }
+
+    // The entries matching `query`, in listing order.
+    pub fn query(&self, query: &Query) -> Vec<Entry> {
+        let used = self.used();
+
+        self.report(View::List, None)
+            .into_iter()
+            .filter(|entry| query.matches(entry, used))
+            .collect()
+    }
+}
+
+pub(crate) fn child_path(path: &str, name: &str) -> String {
+    match path {
+        "/" => format!("/{name}"),
+        _ => format!("{path}/{name}"),
+    }
+}
+
+pub fn render(view: View, entries: &[Entry], format: Format) -> String {
+    let mut output = String::new();
+
+    match format {
+        Format::Text => {
+            for entry in entries {
+                let _ = match view {
+                    View::Tree => writeln!(
+                        output,
+                        "{}- {} ({}, size={})",
+                        "  ".repeat(entry.depth),
+                        entry.name,
+                        entry.kind.name(),
+                        entry.size
+                    ),
+                    View::Du => writeln!(output, "{:>6}  {}", human(entry.size), entry.path),
+                    View::List | View::Top(_) => writeln!(output, "{:<4}  {:>10}  {}", entry.kind.name(), entry.size, entry.path),
+                };
+            }
+        }
+        Format::Json => {
+            let objects: Vec<String> = entries
+                .iter()
+                .map(|entry| {
+                    format!(
+                        "{{\"path\":{},\"name\":{},\"type\":{},\"size\":{},\"depth\":{}}}",
+                        json::string(&entry.path),
+                        json::string(&entry.name),
+                        json::string(entry.kind.name()),
+                        entry.size,
+                        entry.depth,
+                    )
+                })
+                .collect();
+            let _ = writeln!(output, "[{}]", objects.join(","));
+        }
+        Format::Csv => {
+            output.push_str("path,name,type,size,depth\n");
+            for entry in entries {
+                let _ = writeln!(
+                    output,
+                    "{},{},{},{},{}",
+                    csv_field(&entry.path),
+                    csv_field(&entry.name),
+                    entry.kind.name(),
+                    entry.size,
+                    entry.depth
+                );
+            }
+        }
+    }
+
+    output
+}
+
+// Sizes the way `du -h` prints them, in powers of 1024.
+fn human(size: u64) -> String {
+    let mut value = size as f64;
+    let mut unit = "";
+
+    for next in ["K", "M", "G", "T"] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+
+    match unit {
+        "" => size.to_string(),
+        _ if value < 10.0 => format!("{value:.1}{unit}"),
+        _ => format!("{value:.0}{unit}"),
+    }
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_tree() {
+        let fs = crate::example();
+
+        let tree = render(View::Tree, &fs.report(View::Tree, None), Format::Text);
+        assert!(tree.starts_with("- / (dir, size=48381165)\n  - a (dir, size=94853)\n    - e (dir, size=584)\n      - i (file, size=584)\n"));
+
+        let shallow = fs.report(View::Tree, Some(1));
+        assert_eq!(5, shallow.len());
+        assert!(shallow.iter().all(|entry| entry.depth <= 1));
+    }
+
+    #[test]
+    fn render_du_and_top() {
+        let fs = crate::example();
+
+        let du = render(View::Du, &fs.report(View::Du, None), Format::Text);
+        assert_eq!("   46M  /\n   24M  /d\n   93K  /a\n   584  /a/e\n", du);
+
+        let top: Vec<String> = fs.report(View::Top(2), None).into_iter().map(|entry| entry.path).collect();
+        assert_eq!(vec!["/", "/d", "/b.txt", "/c.dat"], top);
+    }
+
+    #[test]
+    fn render_json_and_csv() {
+        let fs = crate::example();
+        let entries = fs.report(View::Du, Some(0));
+
+        assert_eq!(
+            "[{\"path\":\"/\",\"name\":\"/\",\"type\":\"dir\",\"size\":48381165,\"depth\":0}]\n",
+            render(View::Du, &entries, Format::Json)
+        );
+        assert_eq!("path,name,type,size,depth\n/,/,dir,48381165,0\n", render(View::Du, &entries, Format::Csv));
+        assert_eq!("\"a,\"\"b\"\"\"", csv_field("a,\"b\""));
+    }
+}
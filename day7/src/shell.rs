@@ -0,0 +1,130 @@
+use std::fmt::Write;
+
+use crate::{render, FileSystem, Format, Kind, NodeId, Query, View};
+
+const HELP: &str = "cd [PATH]      go to PATH, or to the root
+ls [PATH]      list a directory the way `ls` did in the transcript
+pwd            print the current directory
+du [PATH]      directories under PATH, largest first
+find [EXPR]    paths under the current directory matching a query
+tree [PATH]    the tree under PATH
+exit, quit     leave";
+
+// A working directory over a parsed `FileSystem`, moved around by commands.
+pub struct Shell<'a> {
+    fs: &'a FileSystem,
+    cwd: NodeId,
+}
+
+impl<'a> Shell<'a> {
+    // `None` when the transcript never entered a directory.
+    pub fn new(fs: &'a FileSystem) -> Option<Shell<'a>> {
+        Some(Shell { fs, cwd: fs.root()? })
+    }
+
+    pub fn pwd(&self) -> String {
+        self.fs.path(self.cwd)
+    }
+
+    // Runs one command line, returning what it prints, or `None` when it
+    // leaves the shell.
+    pub fn run(&mut self, line: &str) -> Result<Option<String>, String> {
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let arg = (!rest.is_empty()).then_some(rest);
+
+        let mut output = String::new();
+        match command {
+            "" => {}
+            "cd" => self.cwd = self.resolve(Some(arg.unwrap_or("/")), true)?,
+            "ls" => {
+                let id = self.resolve(arg, false)?;
+                let listed = if self.fs.is_dir(id) { self.fs.children(id) } else { std::slice::from_ref(&id) };
+
+                for &child in listed {
+                    let name = self.fs.name(child);
+                    let _ = match self.fs.kind(child) {
+                        Kind::Dir => writeln!(output, "dir {name}"),
+                        Kind::File => writeln!(output, "{} {name}", self.fs.size(child)),
+                        Kind::Link => writeln!(output, "link {name} -> {}", self.fs.target(child).unwrap_or_default()),
+                    };
+                }
+            }
+            "pwd" => output = format!("{}\n", self.pwd()),
+            "du" => {
+                let id = self.resolve(arg, false)?;
+                let entries = match self.fs.is_dir(id) {
+                    true => self.fs.report_at(id, View::Du, None),
+                    false => self.fs.report_at(id, View::List, None),
+                };
+                output = render(View::Du, &entries, Format::Text);
+            }
+            "find" => {
+                let query = match arg {
+                    Some(expr) => Some(
+                        expr.parse::<Query>()
+                            .map_err(|e| format!("column {}: {}", e.column, e.message))?,
+                    ),
+                    None => None,
+                };
+                let used = self.fs.used();
+
+                for entry in self.fs.report_at(self.cwd, View::List, None) {
+                    if query.as_ref().is_none_or(|query| query.matches(&entry, used)) {
+                        let _ = writeln!(output, "{}", entry.path);
+                    }
+                }
+            }
+            "tree" => {
+                let id = self.resolve(arg, false)?;
+                output = render(View::Tree, &self.fs.report_at(id, View::Tree, None), Format::Text);
+            }
+            "help" => output = format!("{HELP}\n"),
+            "exit" | "quit" => return Ok(None),
+            _ => return Err(format!("unknown command `{command}`, try help")),
+        }
+
+        Ok(Some(output))
+    }
+
+    // `path` from the working directory, which it is without one.
+    fn resolve(&self, path: Option<&str>, dir_only: bool) -> Result<NodeId, String> {
+        match path {
+            Some(path) => self.fs.resolve(self.cwd, path, dir_only).map_err(|(_, message)| message),
+            None => Ok(self.cwd),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(shell: &mut Shell, line: &str) -> Result<String, String> {
+        shell.run(line).map(|output| output.expect("still running"))
+    }
+
+    #[test]
+    fn explore_the_example() {
+        let fs = crate::example();
+        let mut shell = Shell::new(&fs).unwrap();
+
+        assert_eq!("", run(&mut shell, "cd a/e").unwrap());
+        assert_eq!("/a/e\n", run(&mut shell, "pwd").unwrap());
+        assert_eq!("584 i\n", run(&mut shell, "ls").unwrap());
+        assert_eq!("dir e\n29116 f\n2557 g\n62596 h.lst\n", run(&mut shell, "ls ..").unwrap());
+        run(&mut shell, "cd ..").unwrap();
+        assert_eq!("/a/f\n/a/h.lst\n", run(&mut shell, "find type=file and size>10000").unwrap());
+        assert_eq!("- e (dir, size=584)\n  - i (file, size=584)\n", run(&mut shell, "tree e").unwrap());
+        assert_eq!("   24M  /d\n", run(&mut shell, "du /d").unwrap());
+
+        assert_eq!("`f` in /a is a file", run(&mut shell, "cd f").unwrap_err());
+        assert_eq!("no `z` listed in /a", run(&mut shell, "ls z").unwrap_err());
+        assert_eq!("/a", shell.pwd());
+        run(&mut shell, "cd").unwrap();
+        assert_eq!("/", shell.pwd());
+
+        assert_eq!(Ok(None), shell.run("exit"));
+    }
+}